@@ -3,7 +3,7 @@ mod error;
 use clap;
 use env_logger::Builder;
 use log::LevelFilter;
-use nmstate::NetworkState;
+use nmstate::{DnsState, HostNameState, NetworkState, RouteRules, Routes};
 use serde::Serialize;
 use serde_yaml::{self, Value};
 
@@ -121,6 +121,20 @@ fn gen_conf(file_path: &str) -> Result<String, CliError> {
 #[derive(Clone, Debug, PartialEq, Serialize)]
 struct SortedNetworkState {
     interfaces: Vec<Value>,
+    #[serde(skip_serializing_if = "Routes::is_empty")]
+    routes: Routes,
+    #[serde(
+        rename = "route-rules",
+        skip_serializing_if = "RouteRules::is_empty"
+    )]
+    route_rules: RouteRules,
+    #[serde(
+        rename = "dns-resolver",
+        skip_serializing_if = "DnsState::is_empty"
+    )]
+    dns: DnsState,
+    #[serde(skip_serializing_if = "HostNameState::is_empty")]
+    hostname: HostNameState,
 }
 
 const IFACE_TOP_PRIORTIES: [&str; 2] = ["name", "type"];
@@ -131,8 +145,8 @@ fn sort_netstate(
     let mut ifaces = net_state.interfaces.to_vec();
     ifaces.sort_by(|a, b| a.name().cmp(b.name()));
 
+    let mut new_ifaces = Vec::new();
     if let Value::Sequence(ifaces) = serde_yaml::to_value(&ifaces)? {
-        let mut new_ifaces = Vec::new();
         for iface_v in ifaces {
             if let Value::Mapping(iface) = iface_v {
                 let mut new_iface = serde_yaml::Mapping::new();
@@ -158,13 +172,14 @@ fn sort_netstate(
                 new_ifaces.push(Value::Mapping(new_iface));
             }
         }
-        return Ok(SortedNetworkState {
-            interfaces: new_ifaces,
-        });
     }
 
     Ok(SortedNetworkState {
-        interfaces: Vec::new(),
+        interfaces: new_ifaces,
+        routes: net_state.routes,
+        route_rules: net_state.route_rules,
+        dns: net_state.dns,
+        hostname: net_state.hostname,
     })
 }
 
@@ -0,0 +1,382 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{ErrorKind, Interfaces, NmstateError, RouteEntry};
+
+const RESOLV_CONF_PATH: &str = "/etc/resolv.conf";
+
+const IPV4_DEFAULT_GATEWAY: &str = "0.0.0.0/0";
+const IPV6_DEFAULT_GATEWAY: &str = "::/0";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct DnsState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running: Option<DnsClientState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<DnsClientState>,
+}
+
+impl DnsState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_none() && self.config.is_none()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(config) = &self.config {
+            for srv in config.server.as_deref().unwrap_or(&[]) {
+                if let Err(e) = srv.parse::<IpAddr>() {
+                    return Err(NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!("Invalid DNS name server {}: {}", srv, e),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The DNS config from plugin is preferred, the running DNS from
+    // resolv.conf is used when plugin does not manage DNS.
+    pub(crate) fn verify(&self, current: &Self) -> Result<(), NmstateError> {
+        let config = match &self.config {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+        let cur_config = current
+            .config
+            .as_ref()
+            .or_else(|| current.running.as_ref())
+            .cloned()
+            .unwrap_or_default();
+
+        let des_srvs = config.get_sorted_servers();
+        let cur_srvs = cur_config.get_sorted_servers();
+        if des_srvs != cur_srvs {
+            return Err(NmstateError::new(
+                ErrorKind::VerificationError,
+                format!(
+                    "Desired DNS name servers {:?} not match current {:?}",
+                    des_srvs, cur_srvs
+                ),
+            ));
+        }
+        if let Some(des_search) = &config.search {
+            let cur_search = cur_config.search.clone().unwrap_or_default();
+            if des_search != &cur_search {
+                return Err(NmstateError::new(
+                    ErrorKind::VerificationError,
+                    format!(
+                        "Desired DNS search {:?} not match current {:?}",
+                        des_search, cur_search
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct DnsClientState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>,
+}
+
+impl DnsClientState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn get_ipv4_servers(&self) -> Vec<String> {
+        self.get_servers(false)
+    }
+
+    pub(crate) fn get_ipv6_servers(&self) -> Vec<String> {
+        self.get_servers(true)
+    }
+
+    fn get_servers(&self, is_ipv6: bool) -> Vec<String> {
+        let mut ret = Vec::new();
+        for srv in self.server.as_deref().unwrap_or(&[]) {
+            match srv.parse::<IpAddr>() {
+                Ok(ip) => {
+                    if ip.is_ipv6() == is_ipv6 {
+                        ret.push(ip.to_string());
+                    }
+                }
+                Err(e) => {
+                    warn!("Ignoring invalid DNS name server {}: {}", srv, e);
+                }
+            }
+        }
+        ret
+    }
+
+    // Plugins might store IPv4 and IPv6 name servers separately, hence we
+    // only preserve the order within the same IP family.
+    fn get_sorted_servers(&self) -> Vec<String> {
+        let mut ret = self.get_ipv4_servers();
+        ret.extend(self.get_ipv6_servers());
+        ret
+    }
+
+    // Name servers have no interface binding, but plugins like
+    // NetworkManager require them to be stored in the interface holding the
+    // default gateway of the same IP family, in the order of:
+    //  * Interface holding static default gateway in `routes`.
+    //  * Interface with DHCP or IPv6 autoconf enabled in `ifaces`, the
+    //    first `Interfaces` containing the interface with IP config wins.
+    //  * Interface holding default gateway in `running_routes`.
+    // Return the holder interface names for IPv4 and IPv6.
+    pub(crate) fn get_holder_ifaces<'a>(
+        &self,
+        routes: &'a [RouteEntry],
+        ifaces: &[&'a Interfaces],
+        running_routes: &'a [RouteEntry],
+    ) -> Result<(Option<&'a str>, Option<&'a str>), NmstateError> {
+        let ipv4_iface = get_default_gateway_iface(routes, false)
+            .or_else(|| get_dynamic_ip_iface(ifaces, false))
+            .or_else(|| get_default_gateway_iface(running_routes, false));
+        let ipv6_iface = get_default_gateway_iface(routes, true)
+            .or_else(|| get_dynamic_ip_iface(ifaces, true))
+            .or_else(|| get_default_gateway_iface(running_routes, true));
+        let has_ipv4_srvs = !self.get_ipv4_servers().is_empty();
+        let has_ipv6_srvs = !self.get_ipv6_servers().is_empty();
+
+        if has_ipv4_srvs && ipv4_iface.is_none() {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "Failed to find interface with IPv4 default gateway or \
+                DHCP enabled to store IPv4 DNS name servers"
+                    .to_string(),
+            ));
+        }
+        if has_ipv6_srvs && ipv6_iface.is_none() {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "Failed to find interface with IPv6 default gateway or \
+                DHCPv6/autoconf enabled to store IPv6 DNS name servers"
+                    .to_string(),
+            ));
+        }
+        let has_search = !self.search.as_deref().unwrap_or(&[]).is_empty()
+            || !self.options.as_deref().unwrap_or(&[]).is_empty();
+        if has_search && ipv4_iface.is_none() && ipv6_iface.is_none() {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "Failed to find interface with default gateway to store \
+                DNS search and options"
+                    .to_string(),
+            ));
+        }
+        Ok((
+            if has_ipv4_srvs || has_search {
+                ipv4_iface
+            } else {
+                None
+            },
+            if has_ipv6_srvs || (has_search && ipv4_iface.is_none()) {
+                ipv6_iface
+            } else {
+                None
+            },
+        ))
+    }
+}
+
+fn get_default_gateway_iface(
+    routes: &[RouteEntry],
+    is_ipv6: bool,
+) -> Option<&str> {
+    let default_gw = if is_ipv6 {
+        IPV6_DEFAULT_GATEWAY
+    } else {
+        IPV4_DEFAULT_GATEWAY
+    };
+    let mut iface_names: Vec<&str> = routes
+        .iter()
+        .filter(|route| route.destination.as_deref() == Some(default_gw))
+        .filter_map(|route| route.next_hop_iface.as_deref())
+        .collect();
+    // Sort to make sure the same interface is chosen every time
+    iface_names.sort_unstable();
+    iface_names.first().copied()
+}
+
+fn get_dynamic_ip_iface<'a>(
+    ifaces: &[&'a Interfaces],
+    is_ipv6: bool,
+) -> Option<&'a str> {
+    let mut checked_iface_names: HashSet<&str> = HashSet::new();
+    let mut iface_names: Vec<&str> = Vec::new();
+    for iface in ifaces.iter().flat_map(|i| i.kernel_ifaces.values()) {
+        let base_iface = iface.base_iface();
+        let is_dynamic = if is_ipv6 {
+            base_iface
+                .ipv6
+                .as_ref()
+                .map(|ip| ip.enabled && (ip.dhcp || ip.autoconf))
+        } else {
+            base_iface.ipv4.as_ref().map(|ip| ip.enabled && ip.dhcp)
+        };
+        // Interface marked as absent or down, or with IP config defined
+        // hides the same interface in later `Interfaces`.
+        if !iface.is_up() {
+            checked_iface_names.insert(iface.name());
+        } else if let Some(is_dynamic) = is_dynamic {
+            if checked_iface_names.insert(iface.name()) && is_dynamic {
+                iface_names.push(iface.name());
+            }
+        }
+    }
+    // Sort to make sure the same interface is chosen every time
+    iface_names.sort_unstable();
+    iface_names.first().copied()
+}
+
+pub(crate) fn get_running_dns() -> DnsClientState {
+    match std::fs::read_to_string(RESOLV_CONF_PATH) {
+        Ok(content) => parse_resolv_conf(&content),
+        Err(e) => {
+            warn!("Failed to read {}: {}", RESOLV_CONF_PATH, e);
+            DnsClientState::default()
+        }
+    }
+}
+
+fn parse_resolv_conf(content: &str) -> DnsClientState {
+    let mut servers = Vec::new();
+    let mut searches = Vec::new();
+    let mut options = Vec::new();
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("nameserver") => {
+                if let Some(srv) = words.next() {
+                    servers.push(srv.to_string());
+                }
+            }
+            // The last search or domain line wins
+            Some("search") | Some("domain") => {
+                searches = words.map(|w| w.to_string()).collect();
+            }
+            Some("options") => {
+                options.extend(words.map(|w| w.to_string()));
+            }
+            _ => (),
+        }
+    }
+    DnsClientState {
+        server: Some(servers),
+        search: Some(searches),
+        options: Some(options),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gen_dns_conf() -> DnsClientState {
+        DnsClientState {
+            server: Some(vec![
+                "192.0.2.1".to_string(),
+                "2001:db8::1".to_string(),
+            ]),
+            search: Some(vec!["example.org".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    fn gen_default_gw(dst: &str, iface: &str) -> RouteEntry {
+        RouteEntry {
+            destination: Some(dst.to_string()),
+            next_hop_iface: Some(iface.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn gen_ifaces(value: serde_json::Value) -> Interfaces {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_dns_holder_static_gateway() {
+        let routes = vec![
+            gen_default_gw(IPV4_DEFAULT_GATEWAY, "eth2"),
+            gen_default_gw(IPV6_DEFAULT_GATEWAY, "eth1"),
+        ];
+        assert_eq!(
+            gen_dns_conf().get_holder_ifaces(&routes, &[], &[]).unwrap(),
+            (Some("eth2"), Some("eth1"))
+        );
+    }
+
+    #[test]
+    fn test_dns_holder_dhcp_iface() {
+        let des_ifaces = gen_ifaces(serde_json::json!([{
+            "name": "eth1",
+            "type": "ethernet",
+            "ipv4": {"enabled": true, "dhcp": true},
+        }, {
+            "name": "eth3",
+            "type": "ethernet",
+            "state": "absent",
+        }]));
+        let cur_ifaces = gen_ifaces(serde_json::json!([{
+            "name": "eth1",
+            "type": "ethernet",
+            "ipv6": {"enabled": true, "autoconf": true, "dhcp": true},
+        }, {
+            "name": "eth3",
+            "type": "ethernet",
+            "ipv4": {"enabled": true, "dhcp": true},
+        }]));
+        let running_routes = vec![
+            gen_default_gw(IPV4_DEFAULT_GATEWAY, "eth4"),
+            gen_default_gw(IPV6_DEFAULT_GATEWAY, "eth4"),
+        ];
+        assert_eq!(
+            gen_dns_conf()
+                .get_holder_ifaces(
+                    &[],
+                    &[&des_ifaces, &cur_ifaces],
+                    &running_routes
+                )
+                .unwrap(),
+            (Some("eth1"), Some("eth1"))
+        );
+    }
+
+    #[test]
+    fn test_dns_holder_running_routes() {
+        let running_routes = vec![gen_default_gw(IPV4_DEFAULT_GATEWAY, "eth4")];
+        let dns_conf = DnsClientState {
+            server: Some(vec!["192.0.2.1".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            dns_conf
+                .get_holder_ifaces(&[], &[], &running_routes)
+                .unwrap(),
+            (Some("eth4"), None)
+        );
+        let e = gen_dns_conf()
+            .get_holder_ifaces(&[], &[], &running_routes)
+            .unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+    }
+}
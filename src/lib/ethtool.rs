@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+
+// The ethtool command line aliases of kernel feature names
+const ETHTOOL_FEATURE_ALIASES: [(&str, &str); 10] = [
+    ("rx", "rx-checksum"),
+    ("sg", "tx-scatter-gather"),
+    ("tso", "tx-tcp-segmentation"),
+    ("gso", "tx-generic-segmentation"),
+    ("gro", "rx-gro"),
+    ("lro", "rx-lro"),
+    ("rxvlan", "rx-vlan-hw-parse"),
+    ("txvlan", "tx-vlan-hw-insert"),
+    ("ntuple", "rx-ntuple-filter"),
+    ("rxhash", "rx-hashing"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause: Option<EthtoolPauseConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature: Option<BTreeMap<String, bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coalesce: Option<EthtoolCoalesceConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ring: Option<EthtoolRingConfig>,
+}
+
+impl EthtoolConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Kernel feature names are used for verification
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(features) = self.feature.as_mut() {
+            *features = features
+                .iter()
+                .map(|(name, enabled)| {
+                    (get_kernel_feature_name(name).to_string(), *enabled)
+                })
+                .collect();
+        }
+    }
+}
+
+pub(crate) fn get_kernel_feature_name(name: &str) -> &str {
+    for (alias, kernel_name) in ETHTOOL_FEATURE_ALIASES.iter() {
+        if *alias == name {
+            return kernel_name;
+        }
+    }
+    name
+}
+
+pub(crate) fn get_feature_alias(kernel_name: &str) -> Option<&'static str> {
+    ETHTOOL_FEATURE_ALIASES
+        .iter()
+        .find(|(_, k)| *k == kernel_name)
+        .map(|(alias, _)| *alias)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolPauseConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autoneg: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolRingConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_jumbo: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_mini: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolCoalesceConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_rx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_tx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pkt_rate_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pkt_rate_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_sample_interval: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats_block_usecs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs_low: Option<u32>,
+}
+
+impl EthtoolCoalesceConfig {
+    // Desired coalesce options indexed by the property name, boolean options
+    // are stored as 0 or 1.
+    pub(crate) fn to_props(&self) -> HashMap<String, u32> {
+        let mut ret = HashMap::new();
+        if let Ok(serde_json::Value::Object(props)) = serde_json::to_value(self)
+        {
+            for (name, value) in props {
+                if let Some(v) = value.as_bool().map(u32::from).or_else(|| {
+                    value.as_u64().and_then(|v| u32::try_from(v).ok())
+                }) {
+                    ret.insert(name, v);
+                }
+            }
+        }
+        ret
+    }
+}
@@ -0,0 +1,157 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{ErrorKind, NmstateError};
+
+const KERNEL_HOSTNAME_PATH: &str = "/proc/sys/kernel/hostname";
+const STATIC_HOSTNAME_PATH: &str = "/etc/hostname";
+
+// Linux kernel limit, see HOST_NAME_MAX
+const HOSTNAME_MAX_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct HostNameState {
+    // Cannot be empty, kernel always holds a running hostname
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running: Option<String>,
+    // Empty string removes the static hostname
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+}
+
+impl HostNameState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_none() && self.config.is_none()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        for hostname in self.running.iter().chain(self.config.iter()) {
+            if hostname.len() > HOSTNAME_MAX_LEN {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Hostname {} is longer than {} characters",
+                        hostname, HOSTNAME_MAX_LEN
+                    ),
+                ));
+            }
+        }
+        if self.running.as_deref() == Some("") {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "Running hostname cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn verify(&self, current: &Self) -> Result<(), NmstateError> {
+        if let Some(running) = self.running.as_ref() {
+            if Some(running) != current.running.as_ref() {
+                return Err(NmstateError::new(
+                    ErrorKind::VerificationError,
+                    format!(
+                        "Desired running hostname {} not match current {:?}",
+                        running, current.running
+                    ),
+                ));
+            }
+        }
+        if let Some(config) = self.config.as_ref() {
+            // Empty string means removing static hostname, a missing or
+            // empty static hostname file is read as None
+            let cur_config = current.config.as_deref().unwrap_or("");
+            if config != cur_config {
+                return Err(NmstateError::new(
+                    ErrorKind::VerificationError,
+                    format!(
+                        "Desired static hostname {} not match current {}",
+                        config, cur_config
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn get_hostname_state() -> HostNameState {
+    HostNameState {
+        running: read_hostname_file(KERNEL_HOSTNAME_PATH),
+        config: read_hostname_file(STATIC_HOSTNAME_PATH),
+    }
+}
+
+fn read_hostname_file(path: &str) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => parse_hostname_file(&content),
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to read {}: {}", path, e);
+            }
+            None
+        }
+    }
+}
+
+// Only the first line which is neither empty nor comment holds the
+// hostname, see hostname(5)
+fn parse_hostname_file(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
+
+pub(crate) fn set_running_hostname(hostname: &str) -> Result<(), NmstateError> {
+    std::fs::write(KERNEL_HOSTNAME_PATH, hostname).or_else(|e| {
+        Err(NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!(
+                "Failed to set running hostname {} via {}: {}",
+                hostname, KERNEL_HOSTNAME_PATH, e
+            ),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hostname_file() {
+        assert_eq!(
+            parse_hostname_file("# Set by installer\n\nhost1\nhost2\n"),
+            Some("host1".to_string())
+        );
+        assert_eq!(
+            parse_hostname_file("  host1  \n"),
+            Some("host1".to_string())
+        );
+        assert_eq!(parse_hostname_file("# comment only\n\n"), None);
+        assert_eq!(parse_hostname_file(""), None);
+    }
+
+    #[test]
+    fn test_hostname_verify_removed_static_hostname() {
+        let desired = HostNameState {
+            config: Some(String::new()),
+            ..Default::default()
+        };
+        desired.validate().unwrap();
+        desired.verify(&HostNameState::default()).unwrap();
+        assert!(desired
+            .verify(&HostNameState {
+                config: Some("host1".to_string()),
+                ..Default::default()
+            })
+            .is_err());
+    }
+}
@@ -1,9 +1,13 @@
+use std::any::Any;
+
 use log::warn;
 use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::{
-    state::get_json_value_difference, BaseInterface, ErrorKind,
-    EthernetInterface, LinuxBridgeInterface, NmstateError, VethInterface,
+    state::get_json_value_difference, BaseInterface, BondInterface,
+    DummyInterface, ErrorKind, EthernetInterface, LinuxBridgeInterface,
+    MacVlanInterface, MacVtapInterface, NmstateError, OvsBridgeInterface,
+    OvsInterface, VethInterface, VlanInterface, VrfInterface, VxlanInterface,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -16,6 +20,7 @@ pub enum InterfaceType {
     Loopback,
     MacVlan,
     MacVtap,
+    OvsBridge,
     OvsInterface,
     Tun,
     Veth,
@@ -42,6 +47,7 @@ impl From<&str> for InterfaceType {
             "loopback" => InterfaceType::Loopback,
             "macvlan" => InterfaceType::MacVlan,
             "macvtap" => InterfaceType::MacVtap,
+            "ovs-bridge" => InterfaceType::OvsBridge,
             "ovs-interface" => InterfaceType::OvsInterface,
             "tun" => InterfaceType::Tun,
             "veth" => InterfaceType::Veth,
@@ -54,6 +60,14 @@ impl From<&str> for InterfaceType {
     }
 }
 
+impl InterfaceType {
+    // User space interfaces do not exist in kernel, hence might share the same
+    // name with kernel interfaces.
+    pub(crate) fn is_userspace(&self) -> bool {
+        matches!(self, Self::OvsBridge)
+    }
+}
+
 impl std::fmt::Display for InterfaceType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -67,6 +81,7 @@ impl std::fmt::Display for InterfaceType {
                 InterfaceType::Loopback => "loopback",
                 InterfaceType::MacVlan => "macvlan",
                 InterfaceType::MacVtap => "macvtap",
+                InterfaceType::OvsBridge => "ovsbridge",
                 InterfaceType::OvsInterface => "ovsinterface",
                 InterfaceType::Tun => "tun",
                 InterfaceType::Veth => "veth",
@@ -118,23 +133,33 @@ impl UnknownInterface {
     pub fn new(base: BaseInterface) -> Self {
         Self { base, other: serde_json::Map::new() }
     }
+}
 
-    pub(crate) fn update(&mut self, other_iface: &UnknownInterface) {
-        // TODO: this should be done by Trait
-        self.base.update(&other_iface.base);
+impl NmstateInterface for UnknownInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
     }
 
-    pub(crate) fn pre_verify_cleanup(&mut self) {
-        self.base.pre_verify_cleanup();
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "kebab-case", untagged)]
 pub enum Interface {
+    Bond(BondInterface),
     LinuxBridge(LinuxBridgeInterface),
     Ethernet(EthernetInterface),
     Veth(VethInterface),
+    Vlan(VlanInterface),
+    Vxlan(VxlanInterface),
+    Dummy(DummyInterface),
+    MacVlan(MacVlanInterface),
+    MacVtap(MacVtapInterface),
+    Vrf(VrfInterface),
+    OvsBridge(OvsBridgeInterface),
+    OvsInterface(OvsInterface),
     Unknown(UnknownInterface),
 }
 
@@ -162,6 +187,51 @@ impl<'de> Deserialize<'de> for Interface {
                     .map_err(serde::de::Error::custom)?;
                 Ok(Interface::Veth(inner))
             }
+            Some(InterfaceType::Bond) => {
+                let inner = BondInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Bond(inner))
+            }
+            Some(InterfaceType::Vlan) => {
+                let inner = VlanInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Vlan(inner))
+            }
+            Some(InterfaceType::Vxlan) => {
+                let inner = VxlanInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Vxlan(inner))
+            }
+            Some(InterfaceType::Dummy) => {
+                let inner = DummyInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Dummy(inner))
+            }
+            Some(InterfaceType::MacVlan) => {
+                let inner = MacVlanInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::MacVlan(inner))
+            }
+            Some(InterfaceType::MacVtap) => {
+                let inner = MacVtapInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::MacVtap(inner))
+            }
+            Some(InterfaceType::Vrf) => {
+                let inner = VrfInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::Vrf(inner))
+            }
+            Some(InterfaceType::OvsBridge) => {
+                let inner = OvsBridgeInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::OvsBridge(inner))
+            }
+            Some(InterfaceType::OvsInterface) => {
+                let inner = OvsInterface::deserialize(v)
+                    .map_err(serde::de::Error::custom)?;
+                Ok(Interface::OvsInterface(inner))
+            }
             Some(iface_type) => {
                 warn!("Unsupported interface type {}", iface_type);
                 let inner = UnknownInterface::deserialize(v)
@@ -177,18 +247,121 @@ impl<'de> Deserialize<'de> for Interface {
     }
 }
 
+// Behaviours shared by all interface types. Besides this trait, a new
+// interface type only needs its variant in `Interface` and the type specific
+// conversions of each plugin.
+pub(crate) trait NmstateInterface: NmstateInterfaceUpdate {
+    fn base_iface(&self) -> &BaseInterface;
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface;
+
+    // Merge type specific properties from other interface of the same
+    // type. The base interface is already merged by caller.
+    fn update_specific(&mut self, _other: &Self)
+    where
+        Self: Sized,
+    {
+    }
+
+    // Type specific sanity check of desire state
+    fn validate(&self) -> Result<(), NmstateError> {
+        Ok(())
+    }
+
+    // Type specific cleanup before comparing desire state with current.
+    // The base interface is already cleaned up by caller.
+    fn pre_verify_cleanup_specific(&mut self) {}
+
+    // Remove type specific link properties which should not be reapplied
+    // when cloning current interface into desire state.
+    fn clear_link_conf_specific(&mut self) {}
+
+    // Controller should return its port list, even empty. None means the
+    // port list is not mentioned and the current ports should stay untouched.
+    fn ports(&self) -> Option<Vec<&str>> {
+        None
+    }
+
+    // Ports need to be reapplied for their port settings stored in
+    // controller
+    fn configured_ports(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    // The interface this interface is created on top of
+    fn parent(&self) -> Option<&str> {
+        None
+    }
+}
+
+// Bridge `NmstateInterface::update_specific()` to trait object, so that the
+// interface types do not need to match the `Interface` variant of other.
+pub(crate) trait NmstateInterfaceUpdate {
+    fn as_any(&self) -> &dyn Any;
+
+    fn update_specific_any(&mut self, other: &dyn Any);
+}
+
+impl<T: NmstateInterface + 'static> NmstateInterfaceUpdate for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Caller should make sure both are the same interface type
+    fn update_specific_any(&mut self, other: &dyn Any) {
+        debug_assert!(
+            other.is::<T>(),
+            "BUG: update_specific_any() invoked with different interface type"
+        );
+        if let Some(other) = other.downcast_ref::<T>() {
+            self.update_specific(other);
+        }
+    }
+}
+
 impl Interface {
-    pub fn name(&self) -> &str {
+    pub(crate) fn as_iface(&self) -> &dyn NmstateInterface {
+        match self {
+            Self::Bond(iface) => iface,
+            Self::LinuxBridge(iface) => iface,
+            Self::Ethernet(iface) => iface,
+            Self::Veth(iface) => iface,
+            Self::Vlan(iface) => iface,
+            Self::Vxlan(iface) => iface,
+            Self::Dummy(iface) => iface,
+            Self::MacVlan(iface) => iface,
+            Self::MacVtap(iface) => iface,
+            Self::Vrf(iface) => iface,
+            Self::OvsBridge(iface) => iface,
+            Self::OvsInterface(iface) => iface,
+            Self::Unknown(iface) => iface,
+        }
+    }
+
+    pub(crate) fn as_iface_mut(&mut self) -> &mut dyn NmstateInterface {
         match self {
-            Self::LinuxBridge(iface) => iface.base.name.as_str(),
-            Self::Ethernet(iface) => iface.base.name.as_str(),
-            Self::Veth(iface) => iface.base.name.as_str(),
-            Self::Unknown(iface) => iface.base.name.as_str(),
+            Self::Bond(iface) => iface,
+            Self::LinuxBridge(iface) => iface,
+            Self::Ethernet(iface) => iface,
+            Self::Veth(iface) => iface,
+            Self::Vlan(iface) => iface,
+            Self::Vxlan(iface) => iface,
+            Self::Dummy(iface) => iface,
+            Self::MacVlan(iface) => iface,
+            Self::MacVtap(iface) => iface,
+            Self::Vrf(iface) => iface,
+            Self::OvsBridge(iface) => iface,
+            Self::OvsInterface(iface) => iface,
+            Self::Unknown(iface) => iface,
         }
     }
 
+    pub fn name(&self) -> &str {
+        self.base_iface().name.as_str()
+    }
+
     pub(crate) fn is_userspace(&self) -> bool {
-        false
+        self.iface_type().is_userspace()
     }
 
     pub(crate) fn set_iface_type(&mut self, iface_type: InterfaceType) {
@@ -196,12 +369,7 @@ impl Interface {
     }
 
     pub fn iface_type(&self) -> InterfaceType {
-        match self {
-            Self::LinuxBridge(iface) => iface.base.iface_type.clone(),
-            Self::Ethernet(iface) => iface.base.iface_type.clone(),
-            Self::Veth(iface) => iface.base.iface_type.clone(),
-            Self::Unknown(iface) => iface.base.iface_type.clone(),
-        }
+        self.base_iface().iface_type.clone()
     }
 
     pub fn is_up(&self) -> bool {
@@ -213,98 +381,94 @@ impl Interface {
     }
 
     pub fn base_iface(&self) -> &BaseInterface {
-        match self {
-            Self::LinuxBridge(iface) => &iface.base,
-            Self::Ethernet(iface) => &iface.base,
-            Self::Veth(iface) => &iface.base,
-            Self::Unknown(iface) => &iface.base,
-        }
+        self.as_iface().base_iface()
     }
 
     pub(crate) fn base_iface_mut(&mut self) -> &mut BaseInterface {
-        match self {
-            Self::LinuxBridge(iface) => &mut iface.base,
-            Self::Ethernet(iface) => &mut iface.base,
-            Self::Veth(iface) => &mut iface.base,
-            Self::Unknown(iface) => &mut iface.base,
-        }
+        self.as_iface_mut().base_iface_mut()
+    }
+
+    // Current interface might be reapplied for changes of its controller,
+    // routes or DNS, the link properties should be left untouched.
+    pub(crate) fn clone_for_reapply(&self) -> Self {
+        let mut iface = self.clone();
+        let base_iface = iface.base_iface_mut();
+        base_iface.mac_address = None;
+        base_iface.mtu = None;
+        base_iface.ethtool = None;
+        iface.as_iface_mut().clear_link_conf_specific();
+        iface
     }
 
     pub fn ports(&self) -> Option<Vec<&str>> {
-        match self {
-            Self::LinuxBridge(iface) => iface.ports(),
-            _ => None,
-        }
+        self.as_iface().ports()
+    }
+
+    pub(crate) fn configured_ports(&self) -> Vec<&str> {
+        self.as_iface().configured_ports()
+    }
+
+    pub fn parent(&self) -> Option<&str> {
+        self.as_iface().parent()
     }
 
     pub fn update(&mut self, other: &Interface) {
-        match self {
-            Self::LinuxBridge(iface) => {
-                if let Self::LinuxBridge(other_iface) = other {
-                    iface.update(other_iface);
-                } else {
-                    warn!(
-                        "Don't know how to update linux bridge iface \
-                        with {:?}",
-                        other
-                    );
-                }
-            }
-            Self::Ethernet(iface) => {
-                if let Self::Ethernet(other_iface) = other {
-                    iface.update(other_iface);
-                } else {
-                    warn!(
-                        "Don't know how to update ethernet iface \
-                        with {:?}",
-                        other
-                    );
-                }
-            }
-            Self::Veth(iface) => {
-                if let Self::Veth(other_iface) = other {
-                    iface.update(other_iface);
-                } else {
-                    warn!(
-                        "BUG: Don't know how to update veth iface \
-                        with {:?}",
-                        other
-                    );
-                }
-            }
-            Self::Unknown(iface) => {
-                if let Self::Unknown(other_iface) = other {
-                    iface.update(other_iface);
-                } else {
-                    warn!(
-                        "BUG: Don't know how to update unknown iface \
-                        with {:?}",
-                        other
-                    );
-                }
-            }
+        if std::mem::discriminant(self) == std::mem::discriminant(other) {
+            self.base_iface_mut().update(other.base_iface());
+            self.as_iface_mut()
+                .update_specific_any(other.as_iface().as_any());
+        } else if self.iface_type() == InterfaceType::Veth
+            && other.iface_type() == InterfaceType::Ethernet
+        {
+            // Veth might be managed by plugin as ethernet
+            self.base_iface_mut().update(other.base_iface());
+        } else {
+            warn!(
+                "Don't know how to update {} iface {} with {:?}",
+                self.iface_type(),
+                self.name(),
+                other
+            );
         }
     }
 
-    pub(crate) fn pre_verify_cleanup(&mut self) {
-        match self {
-            Self::LinuxBridge(ref mut iface) => {
-                iface.pre_verify_cleanup();
-            }
-            Self::Ethernet(ref mut iface) => {
-                iface.pre_verify_cleanup();
-            }
-            Self::Veth(ref mut iface) => {
-                iface.pre_verify_cleanup();
-            }
-            Self::Unknown(ref mut iface) => {
-                iface.pre_verify_cleanup();
-            }
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        let base_iface = self.base_iface();
+        if let Some(ipv4) = base_iface.ipv4.as_ref() {
+            ipv4.validate(&base_iface.name)?;
+        }
+        if let Some(ipv6) = base_iface.ipv6.as_ref() {
+            ipv6.validate(&base_iface.name)?;
+        }
+        // OVS bridge only exists in user space
+        if base_iface.iface_type == InterfaceType::OvsBridge
+            && (base_iface.mtu.is_some() || base_iface.mac_address.is_some())
+        {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "MTU and MAC address are not supported by OVS bridge {}, \
+                    please set them on its OVS internal interface instead",
+                    base_iface.name
+                ),
+            ));
         }
+        self.as_iface().validate()
+    }
+
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        self.base_iface_mut().pre_verify_cleanup();
+        self.as_iface_mut().pre_verify_cleanup_specific();
     }
 
     pub(crate) fn verify(&self, current: &Self) -> Result<(), NmstateError> {
         let mut self_clone = self.clone();
+        // Veth is allowed to be desired as ethernet
+        if self.iface_type() == InterfaceType::Ethernet
+            && current.iface_type() == InterfaceType::Veth
+        {
+            self_clone.base_iface_mut().iface_type = InterfaceType::Veth;
+        }
         self_clone.pre_verify_cleanup();
         let self_value = serde_json::to_value(&self_clone)?;
 
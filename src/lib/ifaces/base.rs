@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{InterfaceIpv4, InterfaceIpv6, InterfaceState, InterfaceType};
+use crate::{
+    ErrorKind, EthtoolConfig, InterfaceIpv4, InterfaceIpv6, InterfaceState,
+    InterfaceType, NmstateError,
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
@@ -15,6 +18,15 @@ pub struct BaseInterface {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mac_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u64>,
+    // The MTU range supported by driver, only used for querying
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub min_mtu: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub max_mtu: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ethtool: Option<EthtoolConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ipv4: Option<InterfaceIpv4>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ipv6: Option<InterfaceIpv6>,
@@ -62,6 +74,15 @@ impl BaseInterface {
     }
 
     pub(crate) fn pre_verify_cleanup(&mut self) {
+        // MAC address is case insensitive
+        if let Some(mac) = self.mac_address.as_mut() {
+            *mac = mac.to_uppercase();
+        }
+
+        if let Some(ref mut ethtool) = self.ethtool {
+            ethtool.pre_verify_cleanup()
+        }
+
         if let Some(ref mut ipv4) = self.ipv4 {
             ipv4.pre_verify_cleanup()
         }
@@ -71,8 +92,48 @@ impl BaseInterface {
         }
     }
 
+    pub(crate) fn validate_mtu(
+        &self,
+        current: &Self,
+    ) -> Result<(), NmstateError> {
+        if let Some(mtu) = self.mtu {
+            if let Some(min_mtu) = current.min_mtu {
+                if mtu < min_mtu {
+                    return Err(NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Desired MTU {} of interface {} is smaller than \
+                            the minimum MTU {} supported",
+                            mtu, self.name, min_mtu
+                        ),
+                    ));
+                }
+            }
+            if let Some(max_mtu) = current.max_mtu {
+                if mtu > max_mtu {
+                    return Err(NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Desired MTU {} of interface {} is bigger than \
+                            the maximum MTU {} supported",
+                            mtu, self.name, max_mtu
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // VRF ports and OVS internal interfaces are layer 3 interfaces which can
+    // still hold IP addresses, while OVS bridge only exists in user space.
     pub fn can_have_ip(&self) -> bool {
+        if self.iface_type == InterfaceType::OvsBridge {
+            return false;
+        }
         self.controller == None
+            || self.iface_type == InterfaceType::OvsInterface
+            || self.controller_type == Some(InterfaceType::Vrf)
     }
 }
 
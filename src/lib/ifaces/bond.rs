@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, InterfaceType, NmstateInterface};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BondInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_aggregation: Option<BondConfig>,
+}
+
+impl Default for BondInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Bond,
+                ..Default::default()
+            },
+            link_aggregation: None,
+        }
+    }
+}
+
+impl NmstateInterface for BondInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.link_aggregation.is_some() {
+            self.link_aggregation = other.link_aggregation.clone();
+        }
+    }
+
+    // None means the port list is not mentioned and should stay untouched
+    fn ports(&self) -> Option<Vec<&str>> {
+        self.link_aggregation
+            .as_ref()
+            .and_then(|bond_conf| bond_conf.port.as_ref())
+            .map(|ports| ports.iter().map(|port| port.as_str()).collect())
+    }
+
+    // Kernel does not preserve the order of bond ports
+    fn pre_verify_cleanup_specific(&mut self) {
+        if let Some(ref mut bond_conf) = self.link_aggregation {
+            if let Some(ref mut ports) = bond_conf.port {
+                ports.sort_unstable();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct BondConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<BondMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<BondOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BondMode {
+    #[serde(rename = "balance-rr")]
+    RoundRobin,
+    #[serde(rename = "active-backup")]
+    ActiveBackup,
+    #[serde(rename = "balance-xor")]
+    Xor,
+    #[serde(rename = "broadcast")]
+    Broadcast,
+    #[serde(rename = "802.3ad")]
+    Lacp,
+    #[serde(rename = "balance-tlb")]
+    Tlb,
+    #[serde(rename = "balance-alb")]
+    Alb,
+}
+
+impl std::fmt::Display for BondMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::RoundRobin => "balance-rr",
+                Self::ActiveBackup => "active-backup",
+                Self::Xor => "balance-xor",
+                Self::Broadcast => "broadcast",
+                Self::Lacp => "802.3ad",
+                Self::Tlb => "balance-tlb",
+                Self::Alb => "balance-alb",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct BondOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub miimon: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updelay: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downdelay: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arp_interval: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arp_ip_target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_links: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lacp_rate: Option<BondLacpRate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xmit_hash_policy: Option<BondXmitHashPolicy>,
+}
+
+impl BondOptions {
+    // Options in the format of kernel sysfs, which is also what NM expects
+    pub(crate) fn to_kernel_options(&self) -> Vec<(&'static str, String)> {
+        let mut ret = Vec::new();
+        if let Some(v) = self.miimon {
+            ret.push(("miimon", v.to_string()));
+        }
+        if let Some(v) = self.updelay {
+            ret.push(("updelay", v.to_string()));
+        }
+        if let Some(v) = self.downdelay {
+            ret.push(("downdelay", v.to_string()));
+        }
+        if let Some(v) = self.arp_interval {
+            ret.push(("arp_interval", v.to_string()));
+        }
+        if let Some(v) = &self.arp_ip_target {
+            ret.push(("arp_ip_target", v.to_string()));
+        }
+        if let Some(v) = &self.primary {
+            ret.push(("primary", v.to_string()));
+        }
+        if let Some(v) = self.min_links {
+            ret.push(("min_links", v.to_string()));
+        }
+        if let Some(v) = self.lacp_rate {
+            ret.push(("lacp_rate", v.to_string()));
+        }
+        if let Some(v) = self.xmit_hash_policy {
+            ret.push(("xmit_hash_policy", v.to_string()));
+        }
+        ret
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BondLacpRate {
+    Slow,
+    Fast,
+}
+
+impl std::fmt::Display for BondLacpRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Slow => "slow",
+                Self::Fast => "fast",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BondXmitHashPolicy {
+    #[serde(rename = "layer2")]
+    Layer2,
+    #[serde(rename = "layer3+4")]
+    Layer34,
+    #[serde(rename = "layer2+3")]
+    Layer23,
+    #[serde(rename = "encap2+3")]
+    Encap23,
+    #[serde(rename = "encap3+4")]
+    Encap34,
+}
+
+impl std::fmt::Display for BondXmitHashPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Layer2 => "layer2",
+                Self::Layer34 => "layer3+4",
+                Self::Layer23 => "layer2+3",
+                Self::Encap23 => "encap2+3",
+                Self::Encap34 => "encap3+4",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BondMode, Interface, InterfaceType};
+
+    #[test]
+    fn test_bond_serde_round_trip() {
+        let iface: Interface = serde_json::from_str(
+            r#"{
+                "name": "bond99",
+                "type": "bond",
+                "state": "up",
+                "link-aggregation": {
+                    "mode": "802.3ad",
+                    "options": {
+                        "miimon": 100,
+                        "lacp_rate": "fast",
+                        "xmit_hash_policy": "layer3+4"
+                    },
+                    "port": ["eth1", "eth2"]
+                }
+            }"#,
+        )
+        .unwrap();
+        let bond_iface = match &iface {
+            Interface::Bond(i) => i,
+            _ => panic!("Expecting bond interface, got {:?}", iface),
+        };
+        assert_eq!(bond_iface.base.iface_type, InterfaceType::Bond);
+        let bond_conf = bond_iface.link_aggregation.as_ref().unwrap();
+        assert_eq!(bond_conf.mode, Some(BondMode::Lacp));
+        assert_eq!(
+            bond_conf.options.as_ref().unwrap().to_kernel_options(),
+            vec![
+                ("miimon", "100".to_string()),
+                ("lacp_rate", "fast".to_string()),
+                ("xmit_hash_policy", "layer3+4".to_string()),
+            ]
+        );
+
+        let new_iface: Interface =
+            serde_json::from_str(&serde_json::to_string(&iface).unwrap())
+                .unwrap();
+        assert_eq!(new_iface, iface);
+    }
+
+    #[test]
+    fn test_bond_ports_not_mentioned() {
+        let iface: Interface = serde_json::from_str(
+            r#"{"name": "bond99", "type": "bond", "mtu": 9000}"#,
+        )
+        .unwrap();
+        assert_eq!(iface.ports(), None);
+
+        let iface: Interface = serde_json::from_str(
+            r#"{
+                "name": "bond99",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(iface.ports(), None);
+
+        let iface: Interface = serde_json::from_str(
+            r#"{
+                "name": "bond99",
+                "type": "bond",
+                "link-aggregation": {"port": []}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(iface.ports(), Some(Vec::new()));
+    }
+}
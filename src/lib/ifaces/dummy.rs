@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, InterfaceType, NmstateInterface};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DummyInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+}
+
+impl Default for DummyInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Dummy,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl DummyInterface {
+    pub fn new(base: BaseInterface) -> Self {
+        Self { base }
+    }
+}
+
+impl NmstateInterface for DummyInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, InterfaceType};
+use crate::{BaseInterface, InterfaceType, NmstateInterface};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EthernetInterface {
@@ -11,7 +11,41 @@ pub struct EthernetInterface {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
-pub struct EthernetConfig {}
+#[serde(rename_all = "kebab-case")]
+pub struct EthernetConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_negotiation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplex: Option<EthernetDuplex>,
+}
+
+impl EthernetConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EthernetDuplex {
+    Full,
+    Half,
+}
+
+impl std::fmt::Display for EthernetDuplex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Full => "full",
+                Self::Half => "half",
+            }
+        )
+    }
+}
 
 impl Default for EthernetInterface {
     fn default() -> Self {
@@ -25,13 +59,16 @@ impl Default for EthernetInterface {
     }
 }
 
-impl EthernetInterface {
-    pub(crate) fn update(&mut self, other_iface: &EthernetInterface) {
-        // TODO: this should be done by Trait
-        self.base.update(&other_iface.base);
+impl NmstateInterface for EthernetInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
     }
 
-    pub(crate) fn pre_verify_cleanup(&mut self) {
-        self.base.pre_verify_cleanup();
+    fn clear_link_conf_specific(&mut self) {
+        self.ethernet = None;
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::collections::{hash_map::Entry, BTreeSet, HashMap, HashSet};
 
 use log::warn;
 use serde::{
@@ -6,7 +6,8 @@ use serde::{
 };
 
 use crate::{
-    ErrorKind, Interface, InterfaceState, InterfaceType, NmstateError,
+    ErrorKind, Interface, InterfaceState, InterfaceType, LinuxBridgePortConfig,
+    NmstateError, VethInterface,
 };
 
 #[derive(Clone, Debug, Default)]
@@ -62,6 +63,136 @@ impl Interfaces {
         ifaces
     }
 
+    // Topological order of interfaces: controllers are placed before their
+    // ports, and parents before their children, so that they are created
+    // or activated first. Dependency loop is treated as InvalidArgument.
+    pub(crate) fn to_vec_ordered(
+        &self,
+    ) -> Result<Vec<&Interface>, NmstateError> {
+        let mut ifaces = self.to_vec();
+        // Sort to make sure the same order is generated every time
+        ifaces.sort_unstable_by_key(|iface| {
+            (iface.name().to_string(), iface.iface_type().to_string())
+        });
+
+        let find = |name: &str, iface_type: Option<&InterfaceType>| {
+            ifaces.iter().position(|iface| {
+                iface.name() == name
+                    && match iface_type {
+                        Some(t) if t.is_userspace() => &iface.iface_type() == t,
+                        _ => !iface.is_userspace(),
+                    }
+            })
+        };
+
+        // Edge (a, b) means ifaces[a] should be placed before ifaces[b]
+        let mut edges: HashSet<(usize, usize)> = HashSet::new();
+        for (i, iface) in ifaces.iter().enumerate() {
+            if let Some(parent) = iface.parent() {
+                if let Some(j) = find(parent, None) {
+                    edges.insert((j, i));
+                }
+            }
+            let base_iface = iface.base_iface();
+            if let Some(ctrl) = base_iface.controller.as_deref() {
+                if let Some(j) = find(ctrl, base_iface.controller_type.as_ref())
+                {
+                    edges.insert((j, i));
+                }
+            }
+            for port in iface.ports().unwrap_or_default() {
+                if let Some(j) = find(port, None) {
+                    edges.insert((i, j));
+                }
+            }
+        }
+
+        let mut in_degrees = vec![0usize; ifaces.len()];
+        for (_, j) in edges.iter() {
+            in_degrees[*j] += 1;
+        }
+        let mut ready: BTreeSet<usize> = in_degrees
+            .iter()
+            .enumerate()
+            .filter_map(|(i, d)| if *d == 0 { Some(i) } else { None })
+            .collect();
+        let mut ret: Vec<&Interface> = Vec::new();
+        while let Some(i) = ready.iter().next().copied() {
+            ready.remove(&i);
+            ret.push(ifaces[i]);
+            for (_, j) in edges.iter().filter(|(a, _)| *a == i) {
+                in_degrees[*j] -= 1;
+                if in_degrees[*j] == 0 {
+                    ready.insert(*j);
+                }
+            }
+        }
+
+        if ret.len() != ifaces.len() {
+            // Interfaces depending on the loop are also left unplaced, only
+            // report those which could reach themselves.
+            let loop_iface_names: Vec<&str> = ifaces
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| in_degrees[*i] > 0 && is_in_loop(&edges, *i))
+                .map(|(_, iface)| iface.name())
+                .collect();
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Found dependency loop among interfaces {} via \
+                    controller, port or parent relationships",
+                    loop_iface_names.join(", ")
+                ),
+            ));
+        }
+        Ok(ret)
+    }
+
+    pub(crate) fn get_iface(
+        &self,
+        iface_name: &str,
+        iface_type: InterfaceType,
+    ) -> Option<&Interface> {
+        if iface_type.is_userspace() {
+            self.user_ifaces.get(&(iface_name.to_string(), iface_type))
+        } else {
+            self.kernel_ifaces.get(iface_name)
+        }
+    }
+
+    fn get_iface_mut(
+        &mut self,
+        iface_name: &str,
+        iface_type: InterfaceType,
+    ) -> Option<&mut Interface> {
+        if iface_type.is_userspace() {
+            self.user_ifaces
+                .get_mut(&(iface_name.to_string(), iface_type))
+        } else {
+            self.kernel_ifaces.get_mut(iface_name)
+        }
+    }
+
+    // Linux bridge port configs indexed by port name
+    pub(crate) fn get_br_port_confs(
+        &self,
+    ) -> HashMap<&str, &LinuxBridgePortConfig> {
+        let mut ret = HashMap::new();
+        for iface in self.kernel_ifaces.values() {
+            if let Interface::LinuxBridge(br_iface) = iface {
+                if let Some(ports) =
+                    br_iface.bridge.as_ref().and_then(|c| c.port.as_ref())
+                {
+                    for port_conf in ports {
+                        ret.insert(port_conf.name.as_str(), port_conf);
+                    }
+                }
+            }
+        }
+        ret
+    }
+
     pub fn push(&mut self, iface: Interface) {
         if iface.is_userspace() {
             self.user_ifaces
@@ -72,16 +203,20 @@ impl Interfaces {
     }
 
     pub fn update(&mut self, other: &Self) -> Result<(), NmstateError> {
-        let mut new_ifaces: Vec<&Interface> = Vec::new();
         let other_ifaces = other.to_vec();
         for other_iface in &other_ifaces {
-            // TODO: Handle user space interface
-            match self.kernel_ifaces.get_mut(other_iface.name()) {
+            match self
+                .get_iface_mut(other_iface.name(), other_iface.iface_type())
+            {
                 Some(self_iface) => {
                     self_iface.update(other_iface);
                 }
                 None => {
-                    new_ifaces.push(other_iface);
+                    // User space interfaces are only known by plugins like
+                    // NetworkManager, hence should be included.
+                    if other_iface.is_userspace() {
+                        self.push((*other_iface).clone());
+                    }
                 }
             }
         }
@@ -93,9 +228,8 @@ impl Interfaces {
         current_ifaces: &Self,
     ) -> Result<(), NmstateError> {
         for iface in self.to_vec() {
-            // TODO: Support user space interface
             if let Some(cur_iface) =
-                current_ifaces.kernel_ifaces.get(iface.name())
+                current_ifaces.get_iface(iface.name(), iface.iface_type())
             {
                 iface.verify(cur_iface)?;
             } else {
@@ -124,8 +258,12 @@ impl Interfaces {
 
         for iface in self.to_vec() {
             if !iface.is_absent() {
-                match current.kernel_ifaces.get(iface.name()) {
+                iface.validate()?;
+                match current.get_iface(iface.name(), iface.iface_type()) {
                     Some(cur_iface) => {
+                        iface
+                            .base_iface()
+                            .validate_mtu(cur_iface.base_iface())?;
                         let mut chg_iface = iface.clone();
                         chg_iface.set_iface_type(cur_iface.iface_type());
                         chg_ifaces.push(chg_iface);
@@ -136,7 +274,8 @@ impl Interfaces {
                     }
                 }
             } else {
-                if let Some(cur_iface) = current.kernel_ifaces.get(iface.name())
+                if let Some(cur_iface) =
+                    current.get_iface(iface.name(), iface.iface_type())
                 {
                     if iface.iface_type() != InterfaceType::Unknown
                         && iface.iface_type() != cur_iface.iface_type()
@@ -158,40 +297,227 @@ impl Interfaces {
             }
         }
 
-        handle_changed_ports(&mut add_ifaces, &mut chg_ifaces, &current)?;
+        handle_veth_peers(
+            self,
+            &mut add_ifaces,
+            &mut chg_ifaces,
+            &mut del_ifaces,
+            &current,
+        )?;
+        handle_changed_ports(
+            &mut add_ifaces,
+            &mut chg_ifaces,
+            &del_ifaces,
+            &current,
+        )?;
+        check_parents(&add_ifaces, &chg_ifaces, &del_ifaces, &current)?;
+        check_mtu_with_parents(&add_ifaces, &chg_ifaces, current)?;
+
+        // Fail early on dependency loop before plugins touching the system
+        add_ifaces.to_vec_ordered()?;
+        chg_ifaces.to_vec_ordered()?;
 
-        //
-        // * Set priority to interface base on their child/parent or
-        //   subordinate/controller relationships.
         Ok((add_ifaces, chg_ifaces, del_ifaces))
     }
 }
 
-// Include changed subordinates to chg_ifaces
-// TODO: Support nested bridge/bond/etc
+// The parent of new or changed interface should exist in current or be
+// created by the same desire state.
+fn check_parents(
+    add_ifaces: &Interfaces,
+    chg_ifaces: &Interfaces,
+    del_ifaces: &Interfaces,
+    cur_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in add_ifaces.to_vec().iter().chain(chg_ifaces.to_vec().iter()) {
+        if let Some(parent) = iface.parent() {
+            if del_ifaces.kernel_ifaces.contains_key(parent) {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Interface {} cannot be kept as its parent {} \
+                        is marked as absent",
+                        iface.name(),
+                        parent
+                    ),
+                ));
+            }
+            if !add_ifaces.kernel_ifaces.contains_key(parent)
+                && !cur_ifaces.kernel_ifaces.contains_key(parent)
+            {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Parent {} of {} interface {} not found in system \
+                        or new desire state",
+                        parent,
+                        iface.iface_type(),
+                        iface.name()
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// The new interfaces has no MTU range reported by kernel yet, hence the MTU
+// of interfaces stacked on others is checked against their parents.
+fn check_mtu_with_parents(
+    add_ifaces: &Interfaces,
+    chg_ifaces: &Interfaces,
+    cur_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in add_ifaces.to_vec().iter().chain(chg_ifaces.to_vec().iter()) {
+        let (mtu, parent) = match (iface.base_iface().mtu, iface.parent()) {
+            (Some(mtu), Some(parent)) => (mtu, parent),
+            _ => continue,
+        };
+        let parent_mtu = [add_ifaces, chg_ifaces, cur_ifaces]
+            .iter()
+            .filter_map(|ifaces| ifaces.kernel_ifaces.get(parent))
+            .find_map(|p| p.base_iface().mtu);
+        if let Some(parent_mtu) = parent_mtu {
+            if mtu > parent_mtu {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Desired MTU {} of interface {} is bigger than \
+                        the MTU {} of its parent {}",
+                        mtu,
+                        iface.name(),
+                        parent_mtu,
+                        parent
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Both ends of veth are created and removed together:
+//  * Existing veth desired without veth config or as ethernet inherits the
+//    peer from current.
+//  * The peer of new veth not mentioned in desire state is created
+//    implicitly.
+//  * The peer of removed veth is removed also.
+fn handle_veth_peers(
+    des_ifaces: &Interfaces,
+    add_ifaces: &mut Interfaces,
+    chg_ifaces: &mut Interfaces,
+    del_ifaces: &mut Interfaces,
+    cur_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in chg_ifaces.kernel_ifaces.values_mut() {
+        let cur_veth_conf = match cur_ifaces.kernel_ifaces.get(iface.name()) {
+            Some(Interface::Veth(cur_iface)) => cur_iface.veth.clone(),
+            _ => continue,
+        };
+        match iface {
+            Interface::Veth(veth_iface) => {
+                if veth_iface.veth.is_none() {
+                    veth_iface.veth = cur_veth_conf;
+                }
+            }
+            Interface::Ethernet(eth_iface) => {
+                *iface = Interface::Veth(VethInterface::new(
+                    eth_iface.base.clone(),
+                    cur_veth_conf,
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    let mut new_peers: Vec<Interface> = Vec::new();
+    for iface in add_ifaces.kernel_ifaces.values() {
+        if let Interface::Veth(veth_iface) = iface {
+            if let Some(peer) = veth_iface.peer() {
+                if let Some(peer_iface) = des_ifaces.kernel_ifaces.get(peer) {
+                    if peer_iface.is_absent() {
+                        return Err(NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "Veth interface {} cannot be created as its \
+                                peer {} is marked as absent",
+                                iface.name(),
+                                peer
+                            ),
+                        ));
+                    }
+                } else if !cur_ifaces.kernel_ifaces.contains_key(peer) {
+                    if let Some(peer_iface) = veth_iface.new_peer() {
+                        new_peers.push(Interface::Veth(peer_iface));
+                    }
+                }
+            }
+        }
+    }
+    for peer_iface in new_peers {
+        add_ifaces.push(peer_iface);
+    }
+
+    let mut del_peers: Vec<Interface> = Vec::new();
+    for iface in del_ifaces.kernel_ifaces.values() {
+        if let Interface::Veth(veth_iface) = iface {
+            if let Some(peer) = veth_iface.peer() {
+                if del_ifaces.kernel_ifaces.contains_key(peer) {
+                    continue;
+                }
+                if let Some(peer_iface) = des_ifaces.kernel_ifaces.get(peer) {
+                    if !peer_iface.is_absent() {
+                        return Err(NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "Veth interface {} cannot be removed as its \
+                                peer {} is desired to be kept",
+                                iface.name(),
+                                peer
+                            ),
+                        ));
+                    }
+                }
+                if let Some(cur_peer) = cur_ifaces.kernel_ifaces.get(peer) {
+                    let mut del_peer = cur_peer.clone();
+                    del_peer.base_iface_mut().state = InterfaceState::Absent;
+                    del_peers.push(del_peer);
+                }
+            }
+        }
+    }
+    for del_peer in del_peers {
+        del_ifaces.push(del_peer);
+    }
+    Ok(())
+}
+
+// Include changed subordinates to chg_ifaces.
+// The port could be a controller itself, new or existing, which is how
+// nested controllers like bridge over bond are supported. Port moving from
+// one controller to another is detached from the old one and attached to the
+// new one in the same transaction.
 fn handle_changed_ports(
     add_ifaces: &mut Interfaces,
     chg_ifaces: &mut Interfaces,
+    del_ifaces: &Interfaces,
     cur_ifaces: &Interfaces,
 ) -> Result<(), NmstateError> {
+    check_port_conflicts(add_ifaces, chg_ifaces, del_ifaces)?;
+
     let mut changed_ports_to_ctrl: HashMap<String, (String, InterfaceType)> =
         HashMap::new();
     let mut detaching_port_names: Vec<String> = Vec::new();
 
+    // Ports not found in system or new desire state are reported as error
+    // at the end.
     for iface in add_ifaces.to_vec() {
         if let Some(port_names) = iface.ports() {
             for port_name in port_names {
-                if cur_ifaces.kernel_ifaces.contains_key(port_name) {
-                    changed_ports_to_ctrl.insert(
-                        port_name.to_string(),
-                        (iface.name().to_string(), iface.iface_type()),
-                    );
-                } else {
-                    // TODO: The port might be also new interface.
-                    // Currently we trust verification found the missing
-                    // undefined port, but we should raise InvalidArgument
-                    // error.
-                }
+                changed_ports_to_ctrl.insert(
+                    port_name.to_string(),
+                    (iface.name().to_string(), iface.iface_type()),
+                );
             }
         }
     }
@@ -203,7 +529,8 @@ fn handle_changed_ports(
                 desire_port_names.insert(port_name.to_string());
             }
             let mut current_port_names: HashSet<String> = HashSet::new();
-            if let Some(cur_iface) = cur_ifaces.kernel_ifaces.get(iface.name())
+            if let Some(cur_iface) =
+                cur_ifaces.get_iface(iface.name(), iface.iface_type())
             {
                 if let Some(cur_port_names) = cur_iface.ports() {
                     for port_name in cur_port_names {
@@ -212,22 +539,20 @@ fn handle_changed_ports(
                 }
             }
 
-            // Attaching new port to controller
+            // Attaching new port to controller, the port conflicts are
+            // already checked by check_port_conflicts()
             for port_name in desire_port_names.difference(&current_port_names) {
-                if let Some((ctrl_name, _)) =
-                    changed_ports_to_ctrl.get(port_name)
+                changed_ports_to_ctrl.insert(
+                    port_name.to_string(),
+                    (iface.name().to_string(), iface.iface_type()),
+                );
+            }
+
+            // Reapplying attached port for updated port settings
+            for port_name in iface.configured_ports() {
+                if current_port_names.contains(port_name)
+                    && !changed_ports_to_ctrl.contains_key(port_name)
                 {
-                    return Err(NmstateError::new(
-                        ErrorKind::InvalidArgument,
-                        format!(
-                            "Port {} cannot be assigned to \
-                                two controller: {} {}",
-                            port_name,
-                            ctrl_name,
-                            iface.name()
-                        ),
-                    ));
-                } else {
                     changed_ports_to_ctrl.insert(
                         port_name.to_string(),
                         (iface.name().to_string(), iface.iface_type()),
@@ -258,7 +583,7 @@ fn handle_changed_ports(
                         cur_ifaces.kernel_ifaces.get(detach_port_name)
                     {
                         if cur_iface.base_iface().controller != None {
-                            let mut iface = cur_iface.clone();
+                            let mut iface = cur_iface.clone_for_reapply();
                             iface.base_iface_mut().controller = None;
                             iface.base_iface_mut().controller_type = None;
                             v.insert(iface);
@@ -280,7 +605,7 @@ fn handle_changed_ports(
                         Some(ctrl_type.clone());
                 }
                 Entry::Vacant(v) => {
-                    let mut iface = cur_iface.clone();
+                    let mut iface = cur_iface.clone_for_reapply();
                     iface.base_iface_mut().controller =
                         Some(ctrl_name.to_string());
                     iface.base_iface_mut().controller_type =
@@ -297,8 +622,8 @@ fn handle_changed_ports(
                 return Err(NmstateError::new(
                     ErrorKind::InvalidArgument,
                     format!(
-                        "Port {} of {} interface {} not found in system or \
-                            new desire state",
+                        "Port {} of {} interface {} is neither found in \
+                        system nor defined in desire state",
                         port_name, ctrl_type, ctrl_name
                     ),
                 ));
@@ -307,3 +632,304 @@ fn handle_changed_ports(
     }
     Ok(())
 }
+
+// A port can only be assigned to one controller and cannot be removed while
+// still listed as port.
+fn check_port_conflicts(
+    add_ifaces: &Interfaces,
+    chg_ifaces: &Interfaces,
+    del_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut port_to_ctrl: HashMap<&str, &str> = HashMap::new();
+    let mut ctrl_ifaces = add_ifaces.to_vec();
+    ctrl_ifaces.extend(chg_ifaces.to_vec());
+    // Sort to make sure the same error is reported every time
+    ctrl_ifaces.sort_unstable_by_key(|iface| iface.name());
+    for iface in ctrl_ifaces {
+        for port_name in iface.ports().unwrap_or_default() {
+            if port_name == iface.name() && !iface.is_userspace() {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("Interface {} cannot be port of itself", port_name),
+                ));
+            }
+            if let Some(other_ctrl) = port_to_ctrl.get(port_name) {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Port {} cannot be assigned to two controllers: \
+                        {} {}",
+                        port_name,
+                        other_ctrl,
+                        iface.name()
+                    ),
+                ));
+            }
+            if del_ifaces.kernel_ifaces.contains_key(port_name) {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Port {} of interface {} is marked as absent",
+                        port_name,
+                        iface.name()
+                    ),
+                ));
+            }
+            port_to_ctrl.insert(port_name, iface.name());
+        }
+    }
+    Ok(())
+}
+
+fn is_in_loop(edges: &HashSet<(usize, usize)>, start: usize) -> bool {
+    let mut visited: HashSet<usize> = HashSet::new();
+    let mut pending: Vec<usize> = vec![start];
+    while let Some(i) = pending.pop() {
+        for (_, j) in edges.iter().filter(|(a, _)| *a == i) {
+            if *j == start {
+                return true;
+            }
+            if visited.insert(*j) {
+                pending.push(*j);
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gen_ifaces(value: serde_json::Value) -> Interfaces {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ordered_names(ifaces: &Interfaces) -> Vec<&str> {
+        ifaces
+            .to_vec_ordered()
+            .unwrap()
+            .iter()
+            .map(|iface| iface.name())
+            .collect()
+    }
+
+    #[test]
+    fn test_ordered_vlan_over_bond_over_ethernet() {
+        let ifaces = gen_ifaces(serde_json::json!([
+            {
+                "name": "bond99.100",
+                "type": "vlan",
+                "vlan": {"base-iface": "bond99", "id": 100},
+            },
+            {
+                "name": "eth1",
+                "type": "ethernet",
+            },
+            {
+                "name": "bond99",
+                "type": "bond",
+                "link-aggregation": {
+                    "mode": "balance-rr",
+                    "port": ["eth1", "eth2"],
+                },
+            },
+            {
+                "name": "eth2",
+                "type": "ethernet",
+            },
+            {
+                "name": "a.10",
+                "type": "vlan",
+                "vlan": {"base-iface": "bond99.100", "id": 10},
+            },
+        ]));
+        assert_eq!(
+            ordered_names(&ifaces),
+            vec!["bond99", "bond99.100", "a.10", "eth1", "eth2"]
+        );
+    }
+
+    #[test]
+    fn test_ordered_dependency_loop() {
+        let ifaces = gen_ifaces(serde_json::json!([
+            {
+                "name": "bond0",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": ["bond1"]},
+            },
+            {
+                "name": "bond1",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": ["bond0"]},
+            },
+            {
+                "name": "bond1.10",
+                "type": "vlan",
+                "vlan": {"base-iface": "bond1", "id": 10},
+            },
+            {
+                "name": "eth1",
+                "type": "ethernet",
+            },
+        ]));
+        let e = ifaces.to_vec_ordered().unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+        assert!(e.msg().contains("bond0, bond1 via"));
+        assert!(!e.msg().contains("bond1.10"));
+        assert!(!e.msg().contains("eth1"));
+    }
+
+    fn get_controller(
+        ifaces: &Interfaces,
+        iface_name: &str,
+    ) -> (Option<String>, Option<InterfaceType>) {
+        let base_iface = ifaces.kernel_ifaces[iface_name].base_iface();
+        (
+            base_iface.controller.clone(),
+            base_iface.controller_type.clone(),
+        )
+    }
+
+    #[test]
+    fn test_apply_bridge_over_new_bond() {
+        let current = gen_ifaces(serde_json::json!([
+            {"name": "eth1", "type": "ethernet"},
+            {"name": "eth2", "type": "ethernet"},
+        ]));
+        let desired = gen_ifaces(serde_json::json!([
+            {
+                "name": "br0",
+                "type": "linux-bridge",
+                "bridge": {"port": [{"name": "bond0"}]},
+            },
+            {
+                "name": "bond0",
+                "type": "bond",
+                "link-aggregation": {
+                    "mode": "balance-rr",
+                    "port": ["eth1", "eth2"],
+                },
+            },
+        ]));
+        let (add_ifaces, chg_ifaces, del_ifaces) =
+            desired.gen_state_for_apply(&current).unwrap();
+
+        assert_eq!(add_ifaces.kernel_ifaces.len(), 2);
+        assert_eq!(
+            get_controller(&add_ifaces, "bond0"),
+            (Some("br0".to_string()), Some(InterfaceType::LinuxBridge))
+        );
+        assert_eq!(chg_ifaces.kernel_ifaces.len(), 2);
+        for port_name in ["eth1", "eth2"].iter() {
+            assert_eq!(
+                get_controller(&chg_ifaces, port_name),
+                (Some("bond0".to_string()), Some(InterfaceType::Bond))
+            );
+        }
+        assert!(del_ifaces.kernel_ifaces.is_empty());
+    }
+
+    #[test]
+    fn test_apply_move_port_between_controllers() {
+        let current = gen_ifaces(serde_json::json!([
+            {
+                "name": "bond0",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": ["eth1"]},
+            },
+            {
+                "name": "bond1",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": []},
+            },
+            {"name": "eth1", "type": "ethernet", "controller": "bond0"},
+        ]));
+        let desired = gen_ifaces(serde_json::json!([
+            {
+                "name": "bond0",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": []},
+            },
+            {
+                "name": "bond1",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": ["eth1"]},
+            },
+        ]));
+        let (add_ifaces, chg_ifaces, _) =
+            desired.gen_state_for_apply(&current).unwrap();
+
+        assert!(add_ifaces.kernel_ifaces.is_empty());
+        assert_eq!(
+            get_controller(&chg_ifaces, "eth1"),
+            (Some("bond1".to_string()), Some(InterfaceType::Bond))
+        );
+
+        let desired = gen_ifaces(serde_json::json!([
+            {
+                "name": "bond0",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": ["eth1"]},
+            },
+            {
+                "name": "bond1",
+                "type": "bond",
+                "link-aggregation": {"mode": "balance-rr", "port": ["eth1"]},
+            },
+        ]));
+        let e = desired.gen_state_for_apply(&current).unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+        assert!(e.msg().contains("two controllers: bond0 bond1"));
+    }
+
+    #[test]
+    fn test_apply_undefined_port() {
+        let current = gen_ifaces(serde_json::json!([
+            {"name": "eth1", "type": "ethernet"},
+        ]));
+        let desired = gen_ifaces(serde_json::json!([{
+            "name": "bond0",
+            "type": "bond",
+            "link-aggregation": {
+                "mode": "balance-rr",
+                "port": ["eth1", "eth9"],
+            },
+        }]));
+        let e = desired.gen_state_for_apply(&current).unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+        assert!(e.msg().contains("eth9"));
+    }
+
+    #[test]
+    fn test_new_vlan_mtu_bigger_than_parent() {
+        let current = gen_ifaces(serde_json::json!([{
+            "name": "eth1",
+            "type": "ethernet",
+            "mtu": 1500,
+        }]));
+        let desired = gen_ifaces(serde_json::json!([{
+            "name": "eth1.100",
+            "type": "vlan",
+            "mtu": 9000,
+            "vlan": {"base-iface": "eth1", "id": 100},
+        }]));
+        let e = desired.gen_state_for_apply(&current).unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+
+        let desired = gen_ifaces(serde_json::json!([
+            {
+                "name": "eth1",
+                "type": "ethernet",
+                "mtu": 9000,
+            },
+            {
+                "name": "eth1.100",
+                "type": "vlan",
+                "mtu": 9000,
+                "vlan": {"base-iface": "eth1", "id": 100},
+            }
+        ]));
+        assert!(desired.gen_state_for_apply(&current).is_ok());
+    }
+}
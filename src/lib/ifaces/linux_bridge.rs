@@ -1,6 +1,17 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, InterfaceType};
+use crate::{BaseInterface, InterfaceType, NmstateInterface, VlanProtocol};
+
+// Copy the options defined in `$other` to `$self`
+macro_rules! update_br_opts {
+    ($self:expr, $other:expr; $($prop:ident),+ $(,)?) => {
+        $(
+            if $other.$prop.is_some() {
+                $self.$prop = $other.$prop.clone();
+            }
+        )+
+    };
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LinuxBridgeInterface {
@@ -22,26 +33,73 @@ impl Default for LinuxBridgeInterface {
     }
 }
 
-impl LinuxBridgeInterface {
-    pub(crate) fn update(&mut self, other_iface: &LinuxBridgeInterface) {
-        // TODO: this should be done by Trait
-        self.base.update(&other_iface.base);
+impl NmstateInterface for LinuxBridgeInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
     }
 
-    pub(crate) fn ports(&self) -> Option<Vec<&str>> {
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    // NetworkManager only knows the bridge options but not the ports, hence
+    // update options and ports separately.
+    fn update_specific(&mut self, other: &Self) {
+        if let Some(other_br_conf) = other.bridge.as_ref() {
+            let br_conf = self.bridge.get_or_insert_with(Default::default);
+            if let Some(other_opts) = other_br_conf.options.as_ref() {
+                br_conf
+                    .options
+                    .get_or_insert_with(Default::default)
+                    .update(other_opts);
+            }
+            if other_br_conf.port.is_some() {
+                br_conf.port = other_br_conf.port.clone();
+            }
+        }
+    }
+
+    // None means the port list is not mentioned and should stay untouched
+    fn ports(&self) -> Option<Vec<&str>> {
+        self.bridge
+            .as_ref()
+            .and_then(|br_conf| br_conf.port.as_ref())
+            .map(|ports| ports.iter().map(|port| port.name.as_str()).collect())
+    }
+
+    // Ports with bridge port settings defined in desire state
+    fn configured_ports(&self) -> Vec<&str> {
         let mut port_names = Vec::new();
-        if let Some(br_conf) = &self.bridge {
-            if let Some(ports) = &br_conf.port {
-                for port in ports {
+        if let Some(ports) = self
+            .bridge
+            .as_ref()
+            .and_then(|br_conf| br_conf.port.as_ref())
+        {
+            for port in ports {
+                if port.stp_hairpin_mode.is_some()
+                    || port.stp_path_cost.is_some()
+                    || port.stp_priority.is_some()
+                    || port.vlan.is_some()
+                {
                     port_names.push(port.name.as_str());
                 }
             }
         }
-        Some(port_names)
+        port_names
     }
 
-    pub(crate) fn pre_verify_cleanup(&mut self) {
-        self.base.pre_verify_cleanup();
+    // Kernel does not preserve the order of bridge ports
+    fn pre_verify_cleanup_specific(&mut self) {
+        if let Some(ref mut br_conf) = self.bridge {
+            if let Some(ref mut ports) = br_conf.port {
+                ports.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+                for port in ports.iter_mut() {
+                    if let Some(ref mut vlan_conf) = port.vlan {
+                        vlan_conf.pre_verify_cleanup();
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -54,6 +112,25 @@ pub struct LinuxBridgeConfig {
     pub port: Option<Vec<LinuxBridgePortConfig>>,
 }
 
+impl LinuxBridgeConfig {
+    // VLAN filtering is implicitly enabled when any port has VLAN config
+    pub(crate) fn vlan_filtering(&self) -> Option<bool> {
+        if let Some(v) =
+            self.options.as_ref().and_then(|opts| opts.vlan_filtering)
+        {
+            Some(v)
+        } else if let Some(true) = self
+            .port
+            .as_ref()
+            .map(|ports| ports.iter().any(|p| p.vlan.is_some()))
+        {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct LinuxBridgePortConfig {
@@ -64,13 +141,161 @@ pub struct LinuxBridgePortConfig {
     pub stp_path_cost: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stp_priority: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<LinuxBridgePortVlanConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct LinuxBridgePortVlanConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_native: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<LinuxBridgePortVlanMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trunk_tags: Option<Vec<LinuxBridgePortTrunkTag>>,
+}
+
+impl LinuxBridgePortVlanConfig {
+    // Flatten into VLAN ranges in the form of
+    // (min, max, is_pvid, is_egress_untagged)
+    pub(crate) fn to_vlan_ranges(&self) -> Vec<(u16, u16, bool, bool)> {
+        let mut ret = Vec::new();
+        match self.mode {
+            Some(LinuxBridgePortVlanMode::Access) => {
+                if let Some(tag) = self.tag {
+                    ret.push((tag, tag, true, true));
+                }
+            }
+            _ => {
+                if let Some(trunk_tags) = &self.trunk_tags {
+                    for trunk_tag in trunk_tags {
+                        let (min, max) = trunk_tag.get_vlan_range();
+                        ret.push((min, max, false, false));
+                    }
+                }
+                if self.enable_native == Some(true) {
+                    if let Some(tag) = self.tag {
+                        ret.push((tag, tag, true, true));
+                    }
+                }
+            }
+        }
+        ret
+    }
+
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(ref mut trunk_tags) = self.trunk_tags {
+            trunk_tags.sort_unstable_by_key(|t| t.get_vlan_range());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinuxBridgePortVlanMode {
+    Trunk,
+    Access,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinuxBridgePortTrunkTag {
+    Id(u16),
+    IdRange(LinuxBridgePortVlanRange),
+}
+
+impl LinuxBridgePortTrunkTag {
+    // Return the (min, max) of VLAN ID range
+    pub(crate) fn get_vlan_range(&self) -> (u16, u16) {
+        match self {
+            Self::Id(id) => (*id, *id),
+            Self::IdRange(range) => (range.min, range.max),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct LinuxBridgePortVlanRange {
+    pub min: u16,
+    pub max: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct LinuxBridgeOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_forward_mask: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_max: Option<u32>,
+    // In seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_ageing_time: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_last_member_count: Option<u32>,
+    // All multicast intervals are in 1/100 seconds, the same as kernel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_last_member_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_membership_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_querier: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_querier_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_query_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_query_response_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_query_use_ifaddr: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_router: Option<LinuxBridgeMulticastRouterType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_snooping: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_startup_query_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_startup_query_interval: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stp: Option<LinuxBridgeStpOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan_filtering: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan_protocol: Option<VlanProtocol>,
+}
+
+impl LinuxBridgeOptions {
+    // Only the options defined in `other` are overridden
+    pub(crate) fn update(&mut self, other: &Self) {
+        update_br_opts!(
+            self, other;
+            group_forward_mask,
+            hash_max,
+            mac_ageing_time,
+            multicast_last_member_count,
+            multicast_last_member_interval,
+            multicast_membership_interval,
+            multicast_querier,
+            multicast_querier_interval,
+            multicast_query_interval,
+            multicast_query_response_interval,
+            multicast_query_use_ifaddr,
+            multicast_router,
+            multicast_snooping,
+            multicast_startup_query_count,
+            multicast_startup_query_interval,
+            vlan_filtering,
+            vlan_protocol,
+        );
+        if let Some(other_stp) = other.stp.as_ref() {
+            self.stp
+                .get_or_insert_with(Default::default)
+                .update(other_stp);
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -78,4 +303,122 @@ pub struct LinuxBridgeOptions {
 pub struct LinuxBridgeStpOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enabled: Option<bool>,
+    // In seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_delay: Option<u8>,
+    // In seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hello_time: Option<u8>,
+    // In seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u16>,
+}
+
+impl LinuxBridgeStpOptions {
+    pub(crate) fn update(&mut self, other: &Self) {
+        update_br_opts!(
+            self, other;
+            enabled,
+            forward_delay,
+            hello_time,
+            max_age,
+            priority,
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinuxBridgeMulticastRouterType {
+    Disabled,
+    Auto,
+    Enabled,
+}
+
+impl std::fmt::Display for LinuxBridgeMulticastRouterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Disabled => "disabled",
+                Self::Auto => "auto",
+                Self::Enabled => "enabled",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Interface;
+
+    #[test]
+    fn test_linux_bridge_update_keep_ports() {
+        let mut iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "linux-bridge",
+            "bridge": {
+                "options": {"stp": {"enabled": true}},
+                "port": [{"name": "eth1"}]
+            }
+        }))
+        .unwrap();
+        let other: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "linux-bridge",
+            "bridge": {
+                "options": {"stp": {"enabled": false}}
+            }
+        }))
+        .unwrap();
+        iface.update(&other);
+        assert_eq!(iface.as_iface().ports(), Some(vec!["eth1"]));
+        if let Interface::LinuxBridge(br_iface) = iface {
+            let opts = br_iface.bridge.unwrap().options.unwrap();
+            assert_eq!(opts.stp.unwrap().enabled, Some(false));
+        } else {
+            panic!("Expecting linux bridge interface");
+        }
+    }
+
+    #[test]
+    fn test_linux_bridge_update_merge_options() {
+        let mut iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "linux-bridge",
+            "bridge": {
+                "options": {
+                    "group-forward-mask": 8,
+                    "mac-ageing-time": 300,
+                    "stp": {"enabled": true, "forward-delay": 15}
+                }
+            }
+        }))
+        .unwrap();
+        let other: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "linux-bridge",
+            "bridge": {
+                "options": {
+                    "mac-ageing-time": 600,
+                    "stp": {"enabled": false}
+                }
+            }
+        }))
+        .unwrap();
+        iface.update(&other);
+        if let Interface::LinuxBridge(br_iface) = iface {
+            let opts = br_iface.bridge.unwrap().options.unwrap();
+            assert_eq!(opts.group_forward_mask, Some(8));
+            assert_eq!(opts.mac_ageing_time, Some(600));
+            let stp_opts = opts.stp.unwrap();
+            assert_eq!(stp_opts.enabled, Some(false));
+            assert_eq!(stp_opts.forward_delay, Some(15));
+        } else {
+            panic!("Expecting linux bridge interface");
+        }
+    }
 }
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NmstateError, NmstateInterface,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MacVlanInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_vlan: Option<MacVlanConfig>,
+}
+
+impl Default for MacVlanInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::MacVlan,
+                ..Default::default()
+            },
+            mac_vlan: None,
+        }
+    }
+}
+
+impl NmstateInterface for MacVlanInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.mac_vlan.is_some() {
+            self.mac_vlan = other.mac_vlan.clone();
+        }
+    }
+
+    fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.mac_vlan.as_ref() {
+            conf.mode.validate(&self.base.name)?;
+        }
+        Ok(())
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.mac_vlan.as_ref().map(|cfg| cfg.base_iface.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct MacVlanConfig {
+    pub base_iface: String,
+    pub mode: MacVlanMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promiscuous: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MacVlanMode {
+    Vepa,
+    Bridge,
+    Private,
+    Passthru,
+    Source,
+    Unknown,
+}
+
+impl Default for MacVlanMode {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+impl MacVlanMode {
+    // Unknown is only used for showing mode not supported by nmstate
+    pub(crate) fn validate(
+        &self,
+        iface_name: &str,
+    ) -> Result<(), NmstateError> {
+        if self == &Self::Unknown {
+            Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Mode of interface {} should be one of vepa, bridge, \
+                    private, passthru or source",
+                    iface_name
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ErrorKind, Interface};
+
+    #[test]
+    fn test_mac_vlan_unknown_mode() {
+        for iface_type in ["mac-vlan", "mac-vtap"] {
+            let mut iface_value = serde_json::json!({
+                "name": "mac0",
+                "type": iface_type,
+            });
+            iface_value[iface_type] =
+                serde_json::json!({"base-iface": "eth1", "mode": "unknown"});
+            let iface: Interface = serde_json::from_value(iface_value).unwrap();
+            let e = iface.validate().unwrap_err();
+            assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+        }
+    }
+
+    #[test]
+    fn test_mac_vlan_mode_required() {
+        let result: Result<Interface, _> =
+            serde_json::from_value(serde_json::json!({
+                "name": "mac0",
+                "type": "mac-vlan",
+                "mac-vlan": {"base-iface": "eth1"},
+            }));
+        assert!(result.is_err());
+    }
+}
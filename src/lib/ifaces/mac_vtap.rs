@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, InterfaceType, MacVlanMode, NmstateError, NmstateInterface,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MacVtapInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_vtap: Option<MacVtapConfig>,
+}
+
+impl Default for MacVtapInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::MacVtap,
+                ..Default::default()
+            },
+            mac_vtap: None,
+        }
+    }
+}
+
+impl NmstateInterface for MacVtapInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.mac_vtap.is_some() {
+            self.mac_vtap = other.mac_vtap.clone();
+        }
+    }
+
+    fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.mac_vtap.as_ref() {
+            conf.mode.validate(&self.base.name)?;
+        }
+        Ok(())
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.mac_vtap.as_ref().map(|cfg| cfg.base_iface.as_str())
+    }
+}
+
+// MacVtap shares the same modes with MacVlan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct MacVtapConfig {
+    pub base_iface: String,
+    pub mode: MacVlanMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promiscuous: Option<bool>,
+}
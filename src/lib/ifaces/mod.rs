@@ -1,11 +1,27 @@
 mod base;
+mod bond;
+mod dummy;
 mod ethernet;
 mod inter_ifaces;
 mod linux_bridge;
+mod mac_vlan;
+mod mac_vtap;
+mod ovs;
 mod veth;
+mod vlan;
+mod vrf;
+mod vxlan;
 
 pub use base::*;
+pub use bond::*;
+pub use dummy::*;
 pub use ethernet::*;
 pub use inter_ifaces::*;
 pub use linux_bridge::*;
+pub use mac_vlan::*;
+pub use mac_vtap::*;
+pub use ovs::*;
 pub use veth::*;
+pub use vlan::*;
+pub use vrf::*;
+pub use vxlan::*;
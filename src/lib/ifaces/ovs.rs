@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, InterfaceType, NmstateInterface};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OvsBridgeInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge: Option<OvsBridgeConfig>,
+}
+
+impl Default for OvsBridgeInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::OvsBridge,
+                ..Default::default()
+            },
+            bridge: None,
+        }
+    }
+}
+
+impl NmstateInterface for OvsBridgeInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.bridge.is_some() {
+            self.bridge = other.bridge.clone();
+        }
+    }
+
+    // None means the port list is not mentioned and should stay untouched
+    fn ports(&self) -> Option<Vec<&str>> {
+        self.bridge
+            .as_ref()
+            .and_then(|br_conf| br_conf.port.as_ref())
+            .map(|ports| ports.iter().map(|port| port.name.as_str()).collect())
+    }
+
+    // OVS bridge ports are stored without order
+    fn pre_verify_cleanup_specific(&mut self) {
+        if let Some(ref mut br_conf) = self.bridge {
+            if let Some(ref mut ports) = br_conf.port {
+                ports.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct OvsBridgeConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<OvsBridgeOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Vec<OvsBridgePortConfig>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct OvsBridgeOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stp: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rstp: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcast_snooping_enable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_mode: Option<OvsBridgeFailMode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OvsBridgeFailMode {
+    Secure,
+    Standalone,
+}
+
+impl std::fmt::Display for OvsBridgeFailMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Secure => "secure",
+                Self::Standalone => "standalone",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct OvsBridgePortConfig {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OvsInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+}
+
+impl Default for OvsInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::OvsInterface,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl OvsInterface {
+    pub fn new(base: BaseInterface) -> Self {
+        Self { base }
+    }
+}
+
+impl NmstateInterface for OvsInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ErrorKind, Interface};
+
+    #[test]
+    fn test_ovs_bridge_mtu_not_allowed() {
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "ovs-bridge",
+            "mtu": 1500,
+        }))
+        .unwrap();
+        let e = iface.validate().unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, InterfaceType};
+use crate::{BaseInterface, InterfaceState, InterfaceType, NmstateInterface};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VethInterface {
@@ -22,22 +22,47 @@ impl Default for VethInterface {
                 iface_type: InterfaceType::Veth,
                 ..Default::default()
             },
-            ..Default::default()
+            veth: None,
         }
     }
 }
 
 impl VethInterface {
-    pub(crate) fn update(&mut self, other_iface: &VethInterface) {
-        // TODO: this should be done by Trait
-        self.base.update(&other_iface.base);
-        if let Some(_) = other_iface.veth {
-            self.veth = other_iface.veth.clone();
-        }
+    pub fn new(base: BaseInterface, veth: Option<VethConfig>) -> Self {
+        Self { base, veth }
+    }
+
+    pub(crate) fn peer(&self) -> Option<&str> {
+        self.veth.as_ref().map(|v| v.peer.as_str())
     }
 
-    pub(crate) fn pre_verify_cleanup(&mut self) {
-        self.base.pre_verify_cleanup();
-        self.base.iface_type = InterfaceType::Ethernet;
+    // The peer end not mentioned in desire state is created with the
+    // default settings.
+    pub(crate) fn new_peer(&self) -> Option<Self> {
+        self.peer().map(|peer| {
+            let mut peer_iface = Self::default();
+            peer_iface.base.name = peer.to_string();
+            peer_iface.base.state = InterfaceState::Up;
+            peer_iface.veth = Some(VethConfig {
+                peer: self.base.name.clone(),
+            });
+            peer_iface
+        })
+    }
+}
+
+impl NmstateInterface for VethInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.veth.is_some() {
+            self.veth = other.veth.clone();
+        }
     }
 }
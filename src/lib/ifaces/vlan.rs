@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NmstateError, NmstateInterface,
+};
+
+const VLAN_ID_MIN: u16 = 1;
+const VLAN_ID_MAX: u16 = 4094;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VlanInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<VlanConfig>,
+}
+
+impl Default for VlanInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Vlan,
+                ..Default::default()
+            },
+            vlan: None,
+        }
+    }
+}
+
+impl NmstateInterface for VlanInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.vlan.is_some() {
+            self.vlan = other.vlan.clone();
+        }
+    }
+
+    fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(vlan_conf) = self.vlan.as_ref() {
+            if !(VLAN_ID_MIN..=VLAN_ID_MAX).contains(&vlan_conf.id) {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "VLAN ID {} of interface {} is not in the range of \
+                        {}-{}",
+                        vlan_conf.id, self.base.name, VLAN_ID_MIN, VLAN_ID_MAX
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.vlan.as_ref().map(|cfg| cfg.base_iface.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct VlanConfig {
+    pub base_iface: String,
+    pub id: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<VlanProtocol>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VlanProtocol {
+    #[serde(rename = "802.1q")]
+    Ieee8021Q,
+    #[serde(rename = "802.1ad")]
+    Ieee8021Ad,
+}
+
+impl Default for VlanProtocol {
+    fn default() -> Self {
+        Self::Ieee8021Q
+    }
+}
+
+impl std::fmt::Display for VlanProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Ieee8021Q => "802.1q",
+                Self::Ieee8021Ad => "802.1ad",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ErrorKind, Interface};
+
+    fn gen_vlan_iface(id: u16) -> Interface {
+        serde_json::from_value(serde_json::json!({
+            "name": "eth1.100",
+            "type": "vlan",
+            "vlan": {"base-iface": "eth1", "id": id}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_vlan_id_range() {
+        assert!(gen_vlan_iface(1).validate().is_ok());
+        assert!(gen_vlan_iface(4094).validate().is_ok());
+        for id in [0, 4095] {
+            let e = gen_vlan_iface(id).validate().unwrap_err();
+            assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+        }
+    }
+}
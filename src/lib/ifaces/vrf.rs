@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, InterfaceType, NmstateInterface};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct VrfInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vrf: Option<VrfConfig>,
+}
+
+impl Default for VrfInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Vrf,
+                ..Default::default()
+            },
+            vrf: None,
+        }
+    }
+}
+
+impl NmstateInterface for VrfInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.vrf.is_some() {
+            self.vrf = other.vrf.clone();
+        }
+    }
+
+    // None means the port list is not mentioned and should stay untouched
+    fn ports(&self) -> Option<Vec<&str>> {
+        self.vrf
+            .as_ref()
+            .and_then(|vrf_conf| vrf_conf.port.as_ref())
+            .map(|ports| ports.iter().map(|port| port.as_str()).collect())
+    }
+
+    // Kernel does not preserve the order of VRF ports
+    fn pre_verify_cleanup_specific(&mut self) {
+        if let Some(ref mut vrf_conf) = self.vrf {
+            if let Some(ref mut ports) = vrf_conf.port {
+                ports.sort_unstable();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct VrfConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Vec<String>>,
+    pub route_table_id: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Interface;
+
+    #[test]
+    fn test_vrf_ports_not_mentioned() {
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "vrf0",
+            "type": "vrf",
+            "vrf": {"route-table-id": 100}
+        }))
+        .unwrap();
+        assert_eq!(iface.ports(), None);
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, InterfaceType, NmstateInterface};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VxlanInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vxlan: Option<VxlanConfig>,
+}
+
+impl Default for VxlanInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Vxlan,
+                ..Default::default()
+            },
+            vxlan: None,
+        }
+    }
+}
+
+impl NmstateInterface for VxlanInterface {
+    fn base_iface(&self) -> &BaseInterface {
+        &self.base
+    }
+
+    fn base_iface_mut(&mut self) -> &mut BaseInterface {
+        &mut self.base
+    }
+
+    fn update_specific(&mut self, other: &Self) {
+        if other.vxlan.is_some() {
+            self.vxlan = other.vxlan.clone();
+        }
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.vxlan
+            .as_ref()
+            .and_then(|cfg| cfg.base_iface.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct VxlanConfig {
+    pub id: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_iface: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub learning: Option<bool>,
+}
@@ -1,5 +1,56 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 
+use crate::{ErrorKind, NmstateError};
+
+pub(crate) const IP_ADDR_LIFETIME_FOREVER: &str = "forever";
+
+// Pass the DHCP and IPv6 autoconf options shared by IPv4 and IPv6 in the
+// form of `field => "serialized-name"` to the `$action` macro.
+macro_rules! with_ip_auto_opts {
+    ($action:ident, $($args:expr),+) => {
+        $action!(
+            $($args),+;
+            auto_dns => "auto-dns",
+            auto_gateway => "auto-gateway",
+            auto_routes => "auto-routes",
+            auto_table_id => "auto-route-table-id",
+            auto_route_metric => "auto-route-metric",
+            dhcp_send_hostname => "dhcp-send-hostname",
+            dhcp_custom_hostname => "dhcp-custom-hostname",
+            dhcp_timeout => "dhcp-timeout"
+        )
+    };
+}
+
+// Copy the properties listed in `prop_list` of `$other` to `$self`
+macro_rules! update_ip_props {
+    ($self:expr, $other:expr; $($prop:ident $(=> $name:literal)?),+ $(,)?) => {
+        $(
+            if $other.prop_list.contains(&stringify!($prop)) {
+                $self.$prop = $other.$prop.clone();
+            }
+        )+
+    };
+}
+
+macro_rules! serialize_ip_opts {
+    ($self:expr, $serial_struct:expr; $($prop:ident => $name:literal),+) => {
+        $(
+            if let Some(v) = $self.$prop.as_ref() {
+                $serial_struct.serialize_field($name, v)?;
+            }
+        )+
+    };
+}
+
+macro_rules! has_ip_opts {
+    ($self:expr; $($prop:ident => $name:literal),+) => {
+        false $(|| $self.$prop.is_some())+
+    };
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Default)]
 pub struct InterfaceIpv4 {
     #[serde(default)]
@@ -10,6 +61,24 @@ pub struct InterfaceIpv4 {
     pub dhcp: bool,
     #[serde(rename = "address", default)]
     pub addresses: Vec<InterfaceIpAddr>,
+    #[serde(rename = "auto-dns")]
+    pub auto_dns: Option<bool>,
+    #[serde(rename = "auto-gateway")]
+    pub auto_gateway: Option<bool>,
+    #[serde(rename = "auto-routes")]
+    pub auto_routes: Option<bool>,
+    #[serde(rename = "auto-route-table-id")]
+    pub auto_table_id: Option<u32>,
+    #[serde(rename = "auto-route-metric")]
+    pub auto_route_metric: Option<u32>,
+    #[serde(rename = "dhcp-client-id")]
+    pub dhcp_client_id: Option<String>,
+    #[serde(rename = "dhcp-send-hostname")]
+    pub dhcp_send_hostname: Option<bool>,
+    #[serde(rename = "dhcp-custom-hostname")]
+    pub dhcp_custom_hostname: Option<String>,
+    #[serde(rename = "dhcp-timeout")]
+    pub dhcp_timeout: Option<i32>,
 }
 
 impl Serialize for InterfaceIpv4 {
@@ -29,6 +98,9 @@ impl Serialize for InterfaceIpv4 {
         if self.enabled {
             serial_struct.serialize_field("dhcp", &self.dhcp)?;
             serial_struct.serialize_field("addresses", &self.addresses)?;
+            if self.dhcp {
+                self.serialize_auto_opts(&mut serial_struct)?;
+            }
         }
         serial_struct.end()
     }
@@ -36,18 +108,51 @@ impl Serialize for InterfaceIpv4 {
 
 impl InterfaceIpv4 {
     pub(crate) fn update(&mut self, other: &Self) {
-        if other.prop_list.contains(&"enabled") {
-            self.enabled = other.enabled;
-        }
-        if other.prop_list.contains(&"dhcp") {
-            self.dhcp = other.dhcp;
-        }
-        if other.prop_list.contains(&"addresses") {
-            self.addresses = other.addresses.clone();
-        }
+        update_ip_props!(self, other; enabled, dhcp, addresses, dhcp_client_id);
+        with_ip_auto_opts!(update_ip_props, self, other);
+    }
+
+    // Only static addresses are verified
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        self.addresses.retain(|addr| !addr.is_auto());
     }
 
-    pub(crate) fn pre_verify_cleanup(&mut self) {}
+    fn serialize_auto_opts<S>(
+        &self,
+        serial_struct: &mut S,
+    ) -> Result<(), S::Error>
+    where
+        S: SerializeStruct,
+    {
+        with_ip_auto_opts!(serialize_ip_opts, self, serial_struct);
+        serialize_ip_opts!(
+            self, serial_struct;
+            dhcp_client_id => "dhcp-client-id"
+        );
+        Ok(())
+    }
+
+    pub(crate) fn has_auto_opts(&self) -> bool {
+        with_ip_auto_opts!(has_ip_opts, self) || self.dhcp_client_id.is_some()
+    }
+
+    // The auto options are only valid when DHCP is enabled
+    pub(crate) fn validate(
+        &self,
+        iface_name: &str,
+    ) -> Result<(), NmstateError> {
+        if self.enabled && !self.dhcp && self.has_auto_opts() {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "IPv4 auto options of interface {} are not allowed \
+                    when DHCP is disabled",
+                    iface_name
+                ),
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Default)]
@@ -62,6 +167,27 @@ pub struct InterfaceIpv6 {
     pub autoconf: bool,
     #[serde(rename = "address", default)]
     pub addresses: Vec<InterfaceIpAddr>,
+    #[serde(rename = "auto-dns")]
+    pub auto_dns: Option<bool>,
+    #[serde(rename = "auto-gateway")]
+    pub auto_gateway: Option<bool>,
+    #[serde(rename = "auto-routes")]
+    pub auto_routes: Option<bool>,
+    #[serde(rename = "auto-route-table-id")]
+    pub auto_table_id: Option<u32>,
+    #[serde(rename = "auto-route-metric")]
+    pub auto_route_metric: Option<u32>,
+    #[serde(rename = "dhcp-duid")]
+    pub dhcp_duid: Option<String>,
+    #[serde(rename = "addr-gen-mode")]
+    pub addr_gen_mode: Option<Ipv6AddrGenMode>,
+    pub token: Option<String>,
+    #[serde(rename = "dhcp-send-hostname")]
+    pub dhcp_send_hostname: Option<bool>,
+    #[serde(rename = "dhcp-custom-hostname")]
+    pub dhcp_custom_hostname: Option<String>,
+    #[serde(rename = "dhcp-timeout")]
+    pub dhcp_timeout: Option<i32>,
 }
 
 impl Serialize for InterfaceIpv6 {
@@ -82,6 +208,17 @@ impl Serialize for InterfaceIpv6 {
             serial_struct.serialize_field("dhcp", &self.dhcp)?;
             serial_struct.serialize_field("autoconf", &self.autoconf)?;
             serial_struct.serialize_field("addresses", &self.addresses)?;
+            if self.autoconf {
+                if let Some(v) = self.addr_gen_mode.as_ref() {
+                    serial_struct.serialize_field("addr-gen-mode", v)?;
+                }
+                if let Some(v) = self.token.as_ref() {
+                    serial_struct.serialize_field("token", v)?;
+                }
+            }
+            if self.is_auto() {
+                self.serialize_auto_opts(&mut serial_struct)?;
+            }
         }
         serial_struct.end()
     }
@@ -89,26 +226,66 @@ impl Serialize for InterfaceIpv6 {
 
 impl InterfaceIpv6 {
     pub(crate) fn update(&mut self, other: &Self) {
-        if other.prop_list.contains(&"enabled") {
-            self.enabled = other.enabled;
-        }
-        if other.prop_list.contains(&"dhcp") {
-            self.dhcp = other.dhcp;
-        }
-        if other.prop_list.contains(&"autoconf") {
-            self.dhcp = other.dhcp;
-        }
-        if other.prop_list.contains(&"addresses") {
-            self.addresses = other.addresses.clone();
-        }
+        update_ip_props!(
+            self, other;
+            enabled, dhcp, autoconf, addresses, dhcp_duid, addr_gen_mode, token
+        );
+        with_ip_auto_opts!(update_ip_props, self, other);
     }
 
-    // Remove link-local address
+    // Remove link-local address and dynamic address
     pub(crate) fn pre_verify_cleanup(&mut self) {
         self.addresses.retain(|addr| {
             !is_ipv6_unicast_link_local(&addr.ip, addr.prefix_length)
+                && !addr.is_auto()
         });
     }
+
+    pub(crate) fn is_auto(&self) -> bool {
+        self.dhcp || self.autoconf
+    }
+
+    fn serialize_auto_opts<S>(
+        &self,
+        serial_struct: &mut S,
+    ) -> Result<(), S::Error>
+    where
+        S: SerializeStruct,
+    {
+        with_ip_auto_opts!(serialize_ip_opts, self, serial_struct);
+        serialize_ip_opts!(self, serial_struct; dhcp_duid => "dhcp-duid");
+        Ok(())
+    }
+
+    pub(crate) fn has_auto_opts(&self) -> bool {
+        with_ip_auto_opts!(has_ip_opts, self) || self.dhcp_duid.is_some()
+    }
+
+    // The auto options are only valid when DHCPv6 or autoconf is enabled
+    pub(crate) fn validate(
+        &self,
+        iface_name: &str,
+    ) -> Result<(), NmstateError> {
+        if self.enabled && !self.is_auto() && self.has_auto_opts() {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "IPv6 auto options of interface {} are not allowed \
+                    when both DHCPv6 and autoconf are disabled",
+                    iface_name
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ipv6AddrGenMode {
+    #[serde(rename = "eui64")]
+    Eui64,
+    StablePrivacy,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -116,6 +293,69 @@ impl InterfaceIpv6 {
 pub struct InterfaceIpAddr {
     pub ip: String,
     pub prefix_length: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_life_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_life_time: Option<String>,
+}
+
+impl InterfaceIpAddr {
+    // Address with finite lifetime is from DHCP or IPv6 autoconf
+    pub(crate) fn is_auto(&self) -> bool {
+        self.valid_life_time.is_some()
+            && self.valid_life_time.as_deref() != Some(IP_ADDR_LIFETIME_FOREVER)
+    }
+}
+
+// Canonicalize IP address string so that it could be compared as string
+pub(crate) fn sanitize_ip_addr(addr: &str) -> Result<String, NmstateError> {
+    match addr.parse::<IpAddr>() {
+        Ok(ip) => Ok(ip.to_string()),
+        Err(e) => Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Invalid IP address {}: {}", addr, e),
+        )),
+    }
+}
+
+// Canonicalize IP network string like `2001:DB8::1/64` to `2001:db8::/64`.
+// Without prefix length, it is treated as host network.
+pub(crate) fn sanitize_ip_network(
+    ip_net: &str,
+) -> Result<String, NmstateError> {
+    let (addr, prefix) = match ip_net.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (ip_net, None),
+    };
+    let ip = match addr.parse::<IpAddr>() {
+        Ok(ip) => ip,
+        Err(e) => {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Invalid IP network {}: {}", ip_net, e),
+            ));
+        }
+    };
+    let max_prefix = if ip.is_ipv6() { 128 } else { 32 };
+    let prefix = match prefix.map(|p| p.parse::<u32>()) {
+        None => max_prefix,
+        Some(Ok(p)) if p <= max_prefix => p,
+        _ => {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Invalid prefix length of IP network {}", ip_net),
+            ));
+        }
+    };
+    let ip = match ip {
+        IpAddr::V4(ip) => IpAddr::V4(Ipv4Addr::from(
+            u32::from(ip) & u32::MAX.checked_shl(32 - prefix).unwrap_or(0),
+        )),
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(
+            u128::from(ip) & u128::MAX.checked_shl(128 - prefix).unwrap_or(0),
+        )),
+    };
+    Ok(format!("{}/{}", ip, prefix))
 }
 
 fn is_ipv6_addr(addr: &str) -> bool {
@@ -131,3 +371,110 @@ fn is_ipv6_unicast_link_local(ip: &str, prefix: u32) -> bool {
         && ["fe8", "fe9", "fea", "feb"].contains(&&ip[..3])
         && prefix >= 10
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_auto_opts_serialize() {
+        let ip: InterfaceIpv4 = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "dhcp": true,
+            "auto-dns": false,
+            "auto-route-table-id": 100,
+            "dhcp-client-id": "mac",
+        }))
+        .unwrap();
+        assert!(ip.has_auto_opts());
+        assert_eq!(
+            serde_json::to_value(&ip).unwrap(),
+            serde_json::json!({
+                "enabled": true,
+                "dhcp": true,
+                "addresses": [],
+                "auto-dns": false,
+                "auto-route-table-id": 100,
+                "dhcp-client-id": "mac",
+            })
+        );
+    }
+
+    #[test]
+    fn test_ipv6_auto_opts_serialize_autoconf_only() {
+        let ip: InterfaceIpv6 = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "autoconf": true,
+            "auto-gateway": false,
+            "dhcp-duid": "llt",
+        }))
+        .unwrap();
+        let value = serde_json::to_value(&ip).unwrap();
+        assert_eq!(value["auto-gateway"], serde_json::json!(false));
+        assert_eq!(value["dhcp-duid"], serde_json::json!("llt"));
+    }
+
+    #[test]
+    fn test_ip_update_by_prop_list() {
+        let mut ip: InterfaceIpv6 = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "dhcp": true,
+            "auto-dns": true,
+        }))
+        .unwrap();
+        let other = InterfaceIpv6 {
+            enabled: true,
+            autoconf: true,
+            auto_dns: Some(false),
+            dhcp_duid: Some("ll".to_string()),
+            prop_list: vec!["autoconf", "auto_dns", "dhcp_duid"],
+            ..Default::default()
+        };
+        ip.update(&other);
+        assert!(ip.dhcp);
+        assert!(ip.autoconf);
+        assert_eq!(ip.auto_dns, Some(false));
+        assert_eq!(ip.dhcp_duid.as_deref(), Some("ll"));
+    }
+
+    #[test]
+    fn test_sanitize_ip_network() {
+        assert_eq!(
+            sanitize_ip_network("192.0.2.1/24").unwrap(),
+            "192.0.2.0/24"
+        );
+        assert_eq!(sanitize_ip_network("::/0").unwrap(), "::/0");
+        assert!(sanitize_ip_network("192.0.2.256/24").is_err());
+    }
+
+    #[test]
+    fn test_ip_auto_opts_without_dhcp() {
+        let ipv4: InterfaceIpv4 = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "dhcp": false,
+            "auto-dns": false,
+        }))
+        .unwrap();
+        let e = ipv4.validate("eth1").unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+
+        let ipv6: InterfaceIpv6 = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "dhcp": false,
+            "autoconf": false,
+            "auto-routes": false,
+        }))
+        .unwrap();
+        let e = ipv6.validate("eth1").unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+
+        let ipv6: InterfaceIpv6 = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "dhcp": false,
+            "autoconf": true,
+            "auto-routes": false,
+        }))
+        .unwrap();
+        ipv6.validate("eth1").unwrap();
+    }
+}
@@ -1,20 +1,46 @@
+mod dns;
 mod error;
+mod ethtool;
+mod hostname;
 mod iface;
 mod ifaces;
 mod ip;
 mod net_state;
 mod nispor;
 mod nm;
+mod route;
+mod route_rule;
 mod state;
 
+pub use crate::dns::{DnsClientState, DnsState};
 pub use crate::error::{ErrorKind, NmstateError};
+pub use crate::ethtool::{
+    EthtoolCoalesceConfig, EthtoolConfig, EthtoolPauseConfig, EthtoolRingConfig,
+};
+pub use crate::hostname::HostNameState;
+pub(crate) use crate::iface::NmstateInterface;
 pub use crate::iface::{
     Interface, InterfaceState, InterfaceType, UnknownInterface,
 };
 pub use crate::ifaces::{
-    BaseInterface, EthernetInterface, Interfaces, LinuxBridgeConfig,
-    LinuxBridgeInterface, LinuxBridgeOptions, LinuxBridgePortConfig,
-    LinuxBridgeStpOptions, VethConfig, VethInterface,
+    BaseInterface, BondConfig, BondInterface, BondLacpRate, BondMode,
+    BondOptions, BondXmitHashPolicy, DummyInterface, EthernetConfig,
+    EthernetDuplex, EthernetInterface, Interfaces, LinuxBridgeConfig,
+    LinuxBridgeInterface, LinuxBridgeMulticastRouterType, LinuxBridgeOptions,
+    LinuxBridgePortConfig, LinuxBridgePortTrunkTag, LinuxBridgePortVlanConfig,
+    LinuxBridgePortVlanMode, LinuxBridgePortVlanRange, LinuxBridgeStpOptions,
+    MacVlanConfig, MacVlanInterface, MacVlanMode, MacVtapConfig,
+    MacVtapInterface, OvsBridgeConfig, OvsBridgeFailMode, OvsBridgeInterface,
+    OvsBridgeOptions, OvsBridgePortConfig, OvsInterface, VethConfig,
+    VethInterface, VlanConfig, VlanInterface, VlanProtocol, VrfConfig,
+    VrfInterface, VxlanConfig, VxlanInterface,
+};
+pub use crate::ip::{
+    InterfaceIpAddr, InterfaceIpv4, InterfaceIpv6, Ipv6AddrGenMode,
 };
-pub use crate::ip::{InterfaceIpAddr, InterfaceIpv4, InterfaceIpv6};
 pub use crate::net_state::NetworkState;
+pub use crate::route::{RouteEntry, RouteState, Routes};
+pub use crate::route_rule::{
+    RouteRuleAction, RouteRuleEntry, RouteRuleFamily, RouteRuleState,
+    RouteRules,
+};
@@ -4,12 +4,16 @@ use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    dns::get_running_dns,
+    hostname::{get_hostname_state, set_running_hostname},
     nispor::{nispor_apply, nispor_retrieve},
     nm::{
         nm_apply, nm_checkpoint_create, nm_checkpoint_destroy,
-        nm_checkpoint_rollback, nm_checkpoint_timeout_extend, nm_retrieve,
+        nm_checkpoint_rollback, nm_checkpoint_timeout_extend, nm_gen_conf,
+        nm_retrieve,
     },
-    ErrorKind, Interface, Interfaces, NmstateError,
+    DnsState, ErrorKind, HostNameState, Interface, Interfaces, NmstateError,
+    RouteEntry, RouteRules, Routes,
 };
 
 const VERIFY_RETRY_INTERVAL_MILLISECONDS: u64 = 500;
@@ -20,6 +24,22 @@ const VERIFY_RETRY_COUNT_KERNEL_MODE: usize = 6;
 pub struct NetworkState {
     #[serde(default)]
     pub interfaces: Interfaces,
+    #[serde(default, skip_serializing_if = "Routes::is_empty")]
+    pub routes: Routes,
+    #[serde(
+        rename = "route-rules",
+        default,
+        skip_serializing_if = "RouteRules::is_empty"
+    )]
+    pub route_rules: RouteRules,
+    #[serde(
+        rename = "dns-resolver",
+        default,
+        skip_serializing_if = "DnsState::is_empty"
+    )]
+    pub dns: DnsState,
+    #[serde(default, skip_serializing_if = "HostNameState::is_empty")]
+    pub hostname: HostNameState,
     #[serde(skip)]
     // Contain a list of struct member name which is defined explicitly in
     // desire state instead of generated.
@@ -56,7 +76,15 @@ impl NetworkState {
     }
 
     pub fn retrieve(&mut self) -> Result<&mut Self, NmstateError> {
-        self.interfaces = nispor_retrieve()?.interfaces;
+        let np_state = nispor_retrieve()?;
+        self.interfaces = np_state.interfaces;
+        self.routes = np_state.routes;
+        self.route_rules = np_state.route_rules;
+        self.dns = DnsState {
+            running: Some(get_running_dns()),
+            config: None,
+        };
+        self.hostname = get_hostname_state();
         if !self.kernel_only {
             let nm_state = nm_retrieve()?;
             // TODO: Priority handling
@@ -66,8 +94,26 @@ impl NetworkState {
     }
 
     pub fn apply(&self) -> Result<(), NmstateError> {
-        let desire_state_to_edit = self.clone();
-        let desire_state_to_verify = self.clone();
+        self.routes.validate()?;
+        self.route_rules.validate()?;
+        self.dns.validate()?;
+        self.hostname.validate()?;
+        if self.kernel_only && self.hostname.config.is_some() {
+            return Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                "Static hostname is not supported in kernel only mode"
+                    .to_string(),
+            ));
+        }
+        if self.kernel_only && self.dns.config.is_some() {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "DNS config is not supported in kernel only mode".to_string(),
+            ));
+        }
+        let mut desire_state_to_edit = self.clone();
+        desire_state_to_edit.routes.sanitize()?;
+        let desire_state_to_verify = desire_state_to_edit.clone();
         let mut cur_net_state = NetworkState::new();
         cur_net_state.set_kernel_only(self.kernel_only);
         cur_net_state.retrieve()?;
@@ -85,6 +131,9 @@ impl NetworkState {
                     &cur_net_state,
                     &checkpoint,
                 )?;
+                if let Some(running) = chg_net_state.hostname.running.as_ref() {
+                    set_running_hostname(running)?;
+                }
                 nm_checkpoint_timeout_extend(
                     &checkpoint,
                     (VERIFY_RETRY_INTERVAL_MILLISECONDS
@@ -109,6 +158,9 @@ impl NetworkState {
                 &del_net_state,
                 &cur_net_state,
             )?;
+            if let Some(running) = chg_net_state.hostname.running.as_ref() {
+                set_running_hostname(running)?;
+            }
             with_retry(
                 VERIFY_RETRY_INTERVAL_MILLISECONDS,
                 VERIFY_RETRY_COUNT_KERNEL_MODE,
@@ -123,20 +175,63 @@ impl NetworkState {
 
     fn update_state(&mut self, other: &Self) -> Result<(), NmstateError> {
         if other.prop_list.contains(&"interfaces") {
-            self.interfaces.update(&other.interfaces)
-        } else {
-            Ok(())
+            self.interfaces.update(&other.interfaces)?;
         }
+        if other.prop_list.contains(&"dns") {
+            self.dns.config = other.dns.config.clone();
+        }
+        Ok(())
     }
 
+    // Generate the configuration files of each plugin without touching
+    // the system, the keys of returned HashMap are plugin names and the
+    // values are lists of (file_name, file_content).
+    // Hostname and interface removal have no plugin configuration file to
+    // hold them, hence not supported.
     pub fn gen_conf(
         &self,
-    ) -> Result<HashMap<String, Vec<String>>, NmstateError> {
-        todo!()
+    ) -> Result<HashMap<String, Vec<(String, String)>>, NmstateError> {
+        if !self.hostname.is_empty() {
+            return Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                "Hostname is not supported when generating configurations"
+                    .to_string(),
+            ));
+        }
+        if let Some(iface) =
+            self.interfaces.to_vec().into_iter().find(|i| i.is_absent())
+        {
+            return Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "Removing interface {} is not supported when generating \
+                    configurations",
+                    iface.name()
+                ),
+            ));
+        }
+        self.routes.validate()?;
+        self.route_rules.validate()?;
+        self.dns.validate()?;
+        self.hostname.validate()?;
+        let mut desire_state = self.clone();
+        desire_state.routes.sanitize()?;
+        let (add_net_state, chg_net_state, _) =
+            desire_state.gen_state_for_apply(&NetworkState::new())?;
+        let mut ret = HashMap::new();
+        ret.insert(
+            "NetworkManager".to_string(),
+            nm_gen_conf(&add_net_state, &chg_net_state)?,
+        );
+        Ok(ret)
     }
 
     fn verify(&self, current: &Self) -> Result<(), NmstateError> {
-        self.interfaces.verify(&current.interfaces)
+        self.interfaces.verify(&current.interfaces)?;
+        self.routes.verify(&current.routes)?;
+        self.route_rules.verify(&current.route_rules)?;
+        self.dns.verify(&current.dns)?;
+        self.hostname.verify(&current.hostname)
     }
 
     // Return three NetworkState:
@@ -153,9 +248,67 @@ impl NetworkState {
         let mut chg_net_state = NetworkState::new();
         let mut del_net_state = NetworkState::new();
 
-        let (add_ifaces, chg_ifaces, del_ifaces) =
+        let (add_ifaces, mut chg_ifaces, del_ifaces) =
             self.interfaces.gen_state_for_apply(&current.interfaces)?;
 
+        // Routes of removed interfaces are removed by kernel along with
+        // the interface.
+        let mut merged_routes = self.routes.gen_merged_config(&current.routes);
+        merged_routes.retain(|route| {
+            if let Some(iface_name) = route.next_hop_iface.as_ref() {
+                !del_ifaces.kernel_ifaces.contains_key(iface_name)
+            } else {
+                true
+            }
+        });
+        let merged_rules =
+            self.route_rules.gen_merged_config(&current.route_rules);
+
+        // Interfaces holding DNS config should be reapplied.
+        let mut dns_iface_names: Vec<String> = Vec::new();
+        if let Some(dns_conf) = self.dns.config.as_ref() {
+            let (ipv4_iface, ipv6_iface) = dns_conf.get_holder_ifaces(
+                &merged_routes,
+                &[&del_ifaces, &add_ifaces, &chg_ifaces, &current.interfaces],
+                current.routes.running.as_deref().unwrap_or(&[]),
+            )?;
+            dns_iface_names.extend(ipv4_iface.map(|i| i.to_string()));
+            dns_iface_names.extend(ipv6_iface.map(|i| i.to_string()));
+        }
+
+        // Interface with route or route rule changed should be reapplied.
+        let mut changed_iface_names =
+            current.routes.get_changed_ifaces(&merged_routes);
+        changed_iface_names.extend(current.route_rules.get_changed_ifaces(
+            &merged_rules,
+            current.routes.config.as_deref().unwrap_or(&[]),
+            &merged_routes,
+        )?);
+        changed_iface_names.extend(dns_iface_names.iter().map(|i| i.as_str()));
+        for iface_name in changed_iface_names {
+            if add_ifaces.kernel_ifaces.contains_key(iface_name)
+                || chg_ifaces.kernel_ifaces.contains_key(iface_name)
+                || del_ifaces.kernel_ifaces.contains_key(iface_name)
+            {
+                continue;
+            }
+            if let Some(cur_iface) =
+                current.interfaces.kernel_ifaces.get(iface_name)
+            {
+                chg_ifaces.push(cur_iface.clone_for_reapply());
+            } else {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Interface {} holding route, route rule or DNS \
+                        config is not found",
+                        iface_name
+                    ),
+                ));
+            }
+        }
+        check_route_ifaces(&merged_routes, &add_ifaces, &chg_ifaces)?;
+
         debug!("DEBUG, new interfaces {:?}", add_ifaces);
         debug!("DEBUG, chg interfaces {:?}", chg_ifaces);
         debug!("DEBUG, del interfaces {:?}", del_ifaces);
@@ -164,7 +317,14 @@ impl NetworkState {
         add_net_state.prop_list = vec!["interfaces"];
 
         chg_net_state.interfaces = chg_ifaces;
-        chg_net_state.prop_list = vec!["interfaces"];
+        // The full route config is stored in chg_net_state, plugins should
+        // use it for interfaces in add_net_state also.
+        chg_net_state.routes.config = Some(merged_routes);
+        chg_net_state.route_rules.config = Some(merged_rules);
+        chg_net_state.dns.config = self.dns.config.clone();
+        chg_net_state.hostname = self.hostname.clone();
+        chg_net_state.prop_list =
+            vec!["interfaces", "routes", "route_rules", "dns", "hostname"];
 
         del_net_state.interfaces = del_ifaces;
         del_net_state.prop_list = vec!["interfaces"];
@@ -173,6 +333,47 @@ impl NetworkState {
     }
 }
 
+// Routes can only be stored in interface with IP enabled
+fn check_route_ifaces(
+    routes: &[RouteEntry],
+    add_ifaces: &Interfaces,
+    chg_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    for route in routes {
+        let iface_name = match route.next_hop_iface.as_ref() {
+            Some(i) => i,
+            None => continue,
+        };
+        let iface = match add_ifaces
+            .kernel_ifaces
+            .get(iface_name)
+            .or_else(|| chg_ifaces.kernel_ifaces.get(iface_name))
+        {
+            Some(i) => i,
+            None => continue,
+        };
+        let base_iface = iface.base_iface();
+        let ip_enabled = if route.is_ipv6() {
+            base_iface.ipv6.as_ref().map(|i| i.enabled)
+        } else {
+            base_iface.ipv4.as_ref().map(|i| i.enabled)
+        };
+        if !base_iface.can_have_ip() || ip_enabled == Some(false) {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Route {} cannot be applied as its next hop interface \
+                    {} has IP{} disabled",
+                    route,
+                    iface_name,
+                    if route.is_ipv6() { "v6" } else { "v4" }
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn with_nm_checkpoint<T>(checkpoint: &str, func: T) -> Result<(), NmstateError>
 where
     T: FnOnce() -> Result<(), NmstateError>,
@@ -215,3 +416,33 @@ where
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gen_conf_hostname_not_supported() {
+        let net_state: NetworkState =
+            serde_json::from_value(serde_json::json!({
+                "hostname": {"config": "host1"},
+            }))
+            .unwrap();
+        let e = net_state.gen_conf().unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::NotImplementedError));
+    }
+
+    #[test]
+    fn test_gen_conf_absent_iface_not_supported() {
+        let net_state: NetworkState =
+            serde_json::from_value(serde_json::json!({
+                "interfaces": [
+                    {"name": "eth1", "type": "ethernet", "state": "up"},
+                    {"name": "eth2", "type": "ethernet", "state": "absent"},
+                ],
+            }))
+            .unwrap();
+        let e = net_state.gen_conf().unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::NotImplementedError));
+    }
+}
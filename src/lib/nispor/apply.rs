@@ -1,29 +1,176 @@
+use std::collections::{HashMap, HashSet};
+
 use log::warn;
 
 use crate::{
-    nispor::ip::{nmstate_ipv4_to_np, nmstate_ipv6_to_np},
-    ErrorKind, Interface, InterfaceType, NetworkState, NmstateError,
-    VethConfig,
+    nispor::{
+        bond::nmstate_bond_mode_to_np,
+        ip::{nms_ipv6_sysctl_apply, nmstate_ipv4_to_np, nmstate_ipv6_to_np},
+        linux_bridge::{nms_bridge_conf_to_np, nms_port_vlan_conf_to_np},
+        mac_vlan::nmstate_mac_vlan_mode_to_np,
+        route::nms_routes_to_np,
+        route_rule::nms_rules_to_np,
+    },
+    state::get_json_value_difference,
+    BondConfig, ErrorKind, Interface, InterfaceType, LinuxBridgePortConfig,
+    MacVlanConfig, MacVtapConfig, NetworkState, NmstateError, RouteEntry,
+    VethConfig, VlanConfig, VlanProtocol, VrfConfig, VxlanConfig,
 };
 
 pub(crate) fn nispor_apply(
     add_net_state: &NetworkState,
     chg_net_state: &NetworkState,
     del_net_state: &NetworkState,
-    _cur_net_state: &NetworkState,
+    cur_net_state: &NetworkState,
 ) -> Result<(), NmstateError> {
-    apply_single_state(del_net_state)?;
-    apply_single_state(add_net_state)?;
-    apply_single_state(chg_net_state)?;
+    // Linux bridge port settings are stored in controller, while the port
+    // and its controller might be in different state.
+    for iface in add_net_state
+        .interfaces
+        .kernel_ifaces
+        .values()
+        .chain(chg_net_state.interfaces.kernel_ifaces.values())
+    {
+        check_unsupported_conf(
+            iface,
+            cur_net_state.interfaces.kernel_ifaces.get(iface.name()),
+        )?;
+    }
+
+    let mut br_port_confs = add_net_state.interfaces.get_br_port_confs();
+    br_port_confs.extend(chg_net_state.interfaces.get_br_port_confs());
+
+    apply_single_state(del_net_state, &br_port_confs)?;
+    apply_single_state(add_net_state, &br_port_confs)?;
+    apply_single_state(chg_net_state, &br_port_confs)?;
+
+    let mut np_routes = Vec::new();
+    if let Some(merged_routes) = chg_net_state.routes.config.as_ref() {
+        // Routes of removed interfaces are already gone
+        let cur_routes: Vec<RouteEntry> = cur_net_state
+            .routes
+            .config
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .filter(|route| {
+                if let Some(iface_name) = route.next_hop_iface.as_ref() {
+                    !del_net_state
+                        .interfaces
+                        .kernel_ifaces
+                        .contains_key(iface_name)
+                } else {
+                    true
+                }
+            })
+            .cloned()
+            .collect();
+        np_routes = nms_routes_to_np(merged_routes, &cur_routes)?;
+    }
+    let mut np_rules = Vec::new();
+    if let Some(merged_rules) = chg_net_state.route_rules.config.as_ref() {
+        np_rules = nms_rules_to_np(
+            merged_rules,
+            cur_net_state.route_rules.config.as_deref().unwrap_or(&[]),
+        );
+    }
+    if !np_routes.is_empty() || !np_rules.is_empty() {
+        apply_np_net_conf(&nispor::NetConf {
+            ifaces: None,
+            routes: Some(np_routes),
+            rules: Some(np_rules),
+        })?;
+    }
     Ok(())
 }
 
-fn net_state_to_nispor(
-    net_state: &NetworkState,
+// Nispor cannot change ethtool, ethernet link settings and IPv6 token yet.
+// Desired settings identical to current ones are still allowed, so that the
+// output of `show` can be applied back.
+fn check_unsupported_conf(
+    iface: &Interface,
+    cur_iface: Option<&Interface>,
+) -> Result<(), NmstateError> {
+    if let Some(ethtool_conf) = iface.base_iface().ethtool.as_ref() {
+        if !is_current_conf(
+            ethtool_conf,
+            cur_iface.and_then(|i| i.base_iface().ethtool.as_ref()),
+        ) {
+            return Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "Ethtool settings of interface {} is not supported by \
+                    nispor plugin yet",
+                    iface.name()
+                ),
+            ));
+        }
+    }
+    if let Some(token) = iface
+        .base_iface()
+        .ipv6
+        .as_ref()
+        .and_then(|i| i.token.as_ref())
+    {
+        let cur_token = cur_iface
+            .and_then(|i| i.base_iface().ipv6.as_ref())
+            .and_then(|i| i.token.as_ref());
+        if Some(token) != cur_token {
+            return Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "IPv6 token {} of interface {} is not supported by \
+                    nispor plugin yet",
+                    token,
+                    iface.name()
+                ),
+            ));
+        }
+    }
+    if let Interface::Ethernet(eth_iface) = iface {
+        if let Some(eth_conf) = eth_iface.ethernet.as_ref() {
+            let cur_eth_conf = match cur_iface {
+                Some(Interface::Ethernet(i)) => i.ethernet.as_ref(),
+                _ => None,
+            };
+            if !is_current_conf(eth_conf, cur_eth_conf) {
+                return Err(NmstateError::new(
+                    ErrorKind::NotImplementedError,
+                    format!(
+                        "Ethernet link settings of interface {} is not \
+                        supported by nispor plugin yet",
+                        iface.name()
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_current_conf<T: serde::Serialize>(
+    desired: &T,
+    current: Option<&T>,
+) -> bool {
+    match (
+        serde_json::to_value(desired),
+        current.map(serde_json::to_value),
+    ) {
+        (Ok(desired), Some(Ok(current))) => {
+            get_json_value_difference(String::new(), &desired, &current)
+                .is_none()
+        }
+        _ => false,
+    }
+}
+
+fn ifaces_to_nispor(
+    ifaces: &[&Interface],
+    br_port_confs: &HashMap<&str, &LinuxBridgePortConfig>,
 ) -> Result<nispor::NetConf, NmstateError> {
     let mut np_ifaces: Vec<nispor::IfaceConf> = Vec::new();
 
-    for iface in net_state.interfaces.to_vec() {
+    for iface in ifaces {
         if iface.is_up() {
             let np_iface_type = nmstate_iface_type_to_np(&iface.iface_type());
             if np_iface_type == nispor::IfaceType::Unknown {
@@ -34,7 +181,11 @@ fn net_state_to_nispor(
                 );
                 continue;
             }
-            np_ifaces.push(nmstate_iface_to_np(&iface, np_iface_type)?);
+            np_ifaces.push(nmstate_iface_to_np(
+                &iface,
+                np_iface_type,
+                br_port_confs,
+            )?);
         } else if iface.is_absent() {
             np_ifaces.push(nispor::IfaceConf {
                 name: iface.name().to_string(),
@@ -47,6 +198,8 @@ fn net_state_to_nispor(
 
     Ok(nispor::NetConf {
         ifaces: Some(np_ifaces),
+        routes: None,
+        rules: None,
     })
 }
 
@@ -54,9 +207,16 @@ fn nmstate_iface_type_to_np(
     nms_iface_type: &InterfaceType,
 ) -> nispor::IfaceType {
     match nms_iface_type {
+        InterfaceType::Bond => nispor::IfaceType::Bond,
+        InterfaceType::Dummy => nispor::IfaceType::Dummy,
         InterfaceType::LinuxBridge => nispor::IfaceType::Bridge,
         InterfaceType::Ethernet => nispor::IfaceType::Ethernet,
+        InterfaceType::MacVlan => nispor::IfaceType::MacVlan,
+        InterfaceType::MacVtap => nispor::IfaceType::MacVtap,
         InterfaceType::Veth => nispor::IfaceType::Veth,
+        InterfaceType::Vlan => nispor::IfaceType::Vlan,
+        InterfaceType::Vrf => nispor::IfaceType::Vrf,
+        InterfaceType::Vxlan => nispor::IfaceType::Vxlan,
         _ => nispor::IfaceType::Unknown,
     }
 }
@@ -64,6 +224,7 @@ fn nmstate_iface_type_to_np(
 fn nmstate_iface_to_np(
     nms_iface: &Interface,
     np_iface_type: nispor::IfaceType,
+    br_port_confs: &HashMap<&str, &LinuxBridgePortConfig>,
 ) -> Result<nispor::IfaceConf, NmstateError> {
     let mut np_iface = nispor::IfaceConf {
         name: nms_iface.name().to_string(),
@@ -76,16 +237,72 @@ fn nmstate_iface_to_np(
         np_iface.controller = Some(ctrl_name.to_string())
     }
     if base_iface.can_have_ip() {
+        if base_iface.ipv4.as_ref().map(|i| i.has_auto_opts()) == Some(true)
+            || base_iface.ipv6.as_ref().map(|i| i.has_auto_opts()) == Some(true)
+        {
+            return Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "DHCP and IPv6 autoconf options of interface {} are not \
+                    supported by nispor plugin",
+                    nms_iface.name()
+                ),
+            ));
+        }
         np_iface.ipv4 = Some(nmstate_ipv4_to_np(base_iface.ipv4.as_ref()));
         np_iface.ipv6 = Some(nmstate_ipv6_to_np(base_iface.ipv6.as_ref()));
     }
 
     np_iface.mac_address = base_iface.mac_address.clone();
+    np_iface.mtu = base_iface.mtu;
+
+    if base_iface.controller_type == Some(InterfaceType::LinuxBridge) {
+        if let Some(vlan_conf) = br_port_confs
+            .get(base_iface.name.as_str())
+            .and_then(|port_conf| port_conf.vlan.as_ref())
+        {
+            np_iface.bridge_vlan = Some(nms_port_vlan_conf_to_np(vlan_conf));
+        }
+    }
 
     match nms_iface {
         Interface::Veth(veth_iface) => {
             np_iface.veth = nms_veth_conf_to_np(veth_iface.veth.as_ref());
         }
+        Interface::LinuxBridge(br_iface) => {
+            np_iface.bridge =
+                br_iface.bridge.as_ref().map(nms_bridge_conf_to_np);
+        }
+        Interface::Bond(bond_iface) => {
+            np_iface.bond = match bond_iface.link_aggregation.as_ref() {
+                Some(bond_conf) => Some(nms_bond_conf_to_np(bond_conf)?),
+                None => None,
+            };
+        }
+        Interface::Vlan(vlan_iface) => {
+            np_iface.vlan = match vlan_iface.vlan.as_ref() {
+                Some(vlan_conf) => Some(nms_vlan_conf_to_np(vlan_conf)?),
+                None => None,
+            };
+        }
+        Interface::Vxlan(vxlan_iface) => {
+            np_iface.vxlan = nms_vxlan_conf_to_np(vxlan_iface.vxlan.as_ref());
+        }
+        Interface::Vrf(vrf_iface) => {
+            np_iface.vrf = nms_vrf_conf_to_np(vrf_iface.vrf.as_ref());
+        }
+        Interface::MacVlan(mac_vlan_iface) => {
+            np_iface.mac_vlan = match mac_vlan_iface.mac_vlan.as_ref() {
+                Some(conf) => Some(nms_mac_vlan_conf_to_np(conf)?),
+                None => None,
+            };
+        }
+        Interface::MacVtap(mac_vtap_iface) => {
+            np_iface.mac_vtap = match mac_vtap_iface.mac_vtap.as_ref() {
+                Some(conf) => Some(nms_mac_vtap_conf_to_np(conf)?),
+                None => None,
+            };
+        }
         _ => {}
     }
     Ok(np_iface)
@@ -103,8 +320,139 @@ fn nms_veth_conf_to_np(
     }
 }
 
-fn apply_single_state(net_state: &NetworkState) -> Result<(), NmstateError> {
-    let np_net_conf = net_state_to_nispor(net_state)?;
+fn nms_bond_conf_to_np(
+    nms_bond_conf: &BondConfig,
+) -> Result<nispor::BondConf, NmstateError> {
+    if let Some(opts) = &nms_bond_conf.options {
+        if !opts.to_kernel_options().is_empty() {
+            return Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "Bond options are not supported by nispor plugin yet: \
+                    {:?}",
+                    opts
+                ),
+            ));
+        }
+    }
+    Ok(nispor::BondConf {
+        mode: nms_bond_conf.mode.as_ref().map(nmstate_bond_mode_to_np),
+    })
+}
+
+fn nms_vlan_conf_to_np(
+    nms_vlan_conf: &VlanConfig,
+) -> Result<nispor::VlanConf, NmstateError> {
+    if nms_vlan_conf.protocol == Some(VlanProtocol::Ieee8021Ad) {
+        return Err(NmstateError::new(
+            ErrorKind::NotImplementedError,
+            format!(
+                "VLAN protocol {} is not supported by nispor plugin yet",
+                VlanProtocol::Ieee8021Ad
+            ),
+        ));
+    }
+    Ok(nispor::VlanConf {
+        vlan_id: nms_vlan_conf.id,
+        base_iface: nms_vlan_conf.base_iface.to_string(),
+    })
+}
+
+fn nms_vxlan_conf_to_np(
+    nms_vxlan_conf: Option<&VxlanConfig>,
+) -> Option<nispor::VxlanConf> {
+    nms_vxlan_conf.map(|nms_vxlan_conf| nispor::VxlanConf {
+        vxlan_id: nms_vxlan_conf.id,
+        base_iface: nms_vxlan_conf.base_iface.clone(),
+        remote: nms_vxlan_conf.remote.clone(),
+        local: nms_vxlan_conf.local.clone(),
+        dst_port: nms_vxlan_conf.destination_port,
+        learning: nms_vxlan_conf.learning,
+    })
+}
+
+fn nms_vrf_conf_to_np(
+    nms_vrf_conf: Option<&VrfConfig>,
+) -> Option<nispor::VrfConf> {
+    nms_vrf_conf.map(|nms_vrf_conf| nispor::VrfConf {
+        table_id: nms_vrf_conf.route_table_id,
+    })
+}
+
+fn nms_mac_vlan_conf_to_np(
+    nms_mac_vlan_conf: &MacVlanConfig,
+) -> Result<nispor::MacVlanConf, NmstateError> {
+    check_mac_vlan_promiscuous(nms_mac_vlan_conf.promiscuous)?;
+    Ok(nispor::MacVlanConf {
+        base_iface: nms_mac_vlan_conf.base_iface.to_string(),
+        mode: nmstate_mac_vlan_mode_to_np(&nms_mac_vlan_conf.mode),
+    })
+}
+
+fn nms_mac_vtap_conf_to_np(
+    nms_mac_vtap_conf: &MacVtapConfig,
+) -> Result<nispor::MacVtapConf, NmstateError> {
+    check_mac_vlan_promiscuous(nms_mac_vtap_conf.promiscuous)?;
+    Ok(nispor::MacVtapConf {
+        base_iface: nms_mac_vtap_conf.base_iface.to_string(),
+        mode: nmstate_mac_vlan_mode_to_np(&nms_mac_vtap_conf.mode),
+    })
+}
+
+fn check_mac_vlan_promiscuous(
+    promiscuous: Option<bool>,
+) -> Result<(), NmstateError> {
+    if promiscuous == Some(false) {
+        Err(NmstateError::new(
+            ErrorKind::NotImplementedError,
+            "Disabling promiscuous mode of MAC VLAN is not supported by \
+            nispor plugin yet"
+                .to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn apply_single_state(
+    net_state: &NetworkState,
+    br_port_confs: &HashMap<&str, &LinuxBridgePortConfig>,
+) -> Result<(), NmstateError> {
+    let mut ifaces = net_state.interfaces.to_vec_ordered()?;
+    // Ports and children are removed before their controller and parent
+    if ifaces.iter().all(|iface| iface.is_absent()) {
+        ifaces.reverse();
+    }
+    let veth_peers = get_veth_peers(&ifaces);
+    let (peer_ifaces, ifaces): (Vec<&Interface>, Vec<&Interface>) = ifaces
+        .into_iter()
+        .partition(|iface| veth_peers.contains(iface.name()));
+
+    apply_np_net_conf(&ifaces_to_nispor(&ifaces, br_port_confs)?)?;
+    // Veth peers are created or removed along with the other end, only
+    // their own settings are left to apply.
+    let peer_ifaces: Vec<&Interface> = peer_ifaces
+        .into_iter()
+        .filter(|iface| iface.is_up())
+        .collect();
+    if !peer_ifaces.is_empty() {
+        apply_np_net_conf(&ifaces_to_nispor(&peer_ifaces, br_port_confs)?)?;
+    }
+
+    for iface in ifaces.iter().chain(peer_ifaces.iter()) {
+        let base_iface = iface.base_iface();
+        if iface.is_up() && base_iface.can_have_ip() {
+            if let Some(ipv6) = base_iface.ipv6.as_ref() {
+                nms_ipv6_sysctl_apply(iface.name(), ipv6)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_np_net_conf(
+    np_net_conf: &nispor::NetConf,
+) -> Result<(), NmstateError> {
     if let Err(e) = np_net_conf.apply() {
         return Err(NmstateError::new(
             ErrorKind::PluginFailure,
@@ -114,3 +462,148 @@ fn apply_single_state(net_state: &NetworkState) -> Result<(), NmstateError> {
         Ok(())
     }
 }
+
+// Names of veth interfaces whose other end is also in the list.
+fn get_veth_peers<'a>(ifaces: &[&'a Interface]) -> HashSet<&'a str> {
+    let mut ret = HashSet::new();
+    for iface in ifaces {
+        if let Interface::Veth(veth_iface) = iface {
+            if let Some(peer) = veth_iface.peer() {
+                if !ret.contains(iface.name())
+                    && ifaces.iter().any(|i| i.name() == peer)
+                {
+                    ret.insert(peer);
+                }
+            }
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_eth_iface(eth_conf: serde_json::Value) -> Interface {
+        serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "ethernet": eth_conf,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_nispor_ethernet_conf_identical_to_current() {
+        let cur_iface = new_eth_iface(serde_json::json!({
+            "auto-negotiation": false,
+            "speed": 1000,
+            "duplex": "full",
+        }));
+        let iface = new_eth_iface(serde_json::json!({"speed": 1000}));
+
+        check_unsupported_conf(&iface, Some(&cur_iface)).unwrap();
+        check_unsupported_conf(&cur_iface, Some(&cur_iface)).unwrap();
+    }
+
+    #[test]
+    fn test_nispor_ethernet_conf_changed() {
+        let cur_iface = new_eth_iface(serde_json::json!({
+            "auto-negotiation": false,
+            "speed": 1000,
+            "duplex": "full",
+        }));
+        let iface = new_eth_iface(serde_json::json!({"speed": 100}));
+
+        let e = check_unsupported_conf(&iface, Some(&cur_iface)).unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::NotImplementedError));
+        assert!(check_unsupported_conf(&iface, None).is_err());
+    }
+
+    #[test]
+    fn test_nispor_ethtool_conf_identical_to_current() {
+        let cur_iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "ethtool": {
+                "feature": {
+                    "rx-checksum": true,
+                    "tx-generic-segmentation": true,
+                },
+                "ring": {
+                    "rx": 256,
+                    "tx": 256,
+                },
+            },
+        }))
+        .unwrap();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "ethtool": {
+                "feature": {
+                    "rx-checksum": true,
+                },
+            },
+        }))
+        .unwrap();
+
+        check_unsupported_conf(&iface, Some(&cur_iface)).unwrap();
+        check_unsupported_conf(&cur_iface, Some(&cur_iface)).unwrap();
+    }
+
+    #[test]
+    fn test_nispor_ethtool_conf_changed() {
+        let cur_iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "ethtool": {
+                "feature": {
+                    "rx-checksum": true,
+                },
+            },
+        }))
+        .unwrap();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "ethtool": {
+                "feature": {
+                    "rx-checksum": false,
+                },
+            },
+        }))
+        .unwrap();
+
+        let e = check_unsupported_conf(&iface, Some(&cur_iface)).unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::NotImplementedError));
+    }
+
+    #[test]
+    fn test_nispor_ipv6_token() {
+        let cur_iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "ipv6": {
+                "enabled": true,
+                "autoconf": true,
+                "token": "::fac1",
+            },
+        }))
+        .unwrap();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "ipv6": {
+                "enabled": true,
+                "autoconf": true,
+                "token": "::fac2",
+            },
+        }))
+        .unwrap();
+
+        check_unsupported_conf(&cur_iface, Some(&cur_iface)).unwrap();
+        let e = check_unsupported_conf(&iface, Some(&cur_iface)).unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::NotImplementedError));
+    }
+}
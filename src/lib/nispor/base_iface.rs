@@ -1,4 +1,7 @@
+use std::convert::TryFrom;
+
 use crate::{
+    nispor::ethtool::np_ethtool_to_nmstate,
     nispor::ip::{np_ipv4_to_nmstate, np_ipv6_to_nmstate},
     BaseInterface, InterfaceState, InterfaceType,
 };
@@ -24,6 +27,17 @@ fn np_iface_type_to_nmstate(
     }
 }
 
+fn np_controller_type_to_nmstate(
+    np_ctrl_type: &nispor::ControllerType,
+) -> InterfaceType {
+    match np_ctrl_type {
+        nispor::ControllerType::Bond => InterfaceType::Bond,
+        nispor::ControllerType::Bridge => InterfaceType::LinuxBridge,
+        nispor::ControllerType::Vrf => InterfaceType::Vrf,
+        _ => InterfaceType::Other(format!("{:?}", np_ctrl_type)),
+    }
+}
+
 fn np_iface_state_to_nmstate(
     np_iface_state: &nispor::IfaceState,
 ) -> InterfaceState {
@@ -44,10 +58,18 @@ pub(crate) fn np_iface_to_base_iface(
         ipv4: np_ipv4_to_nmstate(np_iface),
         ipv6: np_ipv6_to_nmstate(np_iface),
         mac_address: Some(np_iface.mac_address.to_string()),
+        ethtool: np_iface.ethtool.as_ref().map(np_ethtool_to_nmstate),
+        mtu: u64::try_from(np_iface.mtu).ok(),
+        min_mtu: np_iface.min_mtu.and_then(|m| u64::try_from(m).ok()),
+        max_mtu: np_iface.max_mtu.and_then(|m| u64::try_from(m).ok()),
         controller: np_iface
             .controller
             .as_ref()
             .and_then(|c| Some(c.to_string())),
+        controller_type: np_iface
+            .controller_type
+            .as_ref()
+            .map(np_controller_type_to_nmstate),
         ..Default::default()
     };
     base_iface
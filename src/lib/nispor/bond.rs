@@ -0,0 +1,79 @@
+use crate::{
+    BaseInterface, BondConfig, BondInterface, BondLacpRate, BondMode,
+    BondOptions, BondXmitHashPolicy,
+};
+
+pub(crate) fn np_bond_to_nmstate(
+    np_iface: nispor::Iface,
+    base_iface: BaseInterface,
+) -> BondInterface {
+    let bond_conf = np_iface.bond.as_ref().map(|np_bond| BondConfig {
+        mode: np_bond_mode_to_nmstate(&np_bond.mode),
+        options: Some(np_bond_options_to_nmstate(np_bond)),
+        port: Some(np_bond.subordinates.clone()),
+    });
+    BondInterface {
+        base: base_iface,
+        link_aggregation: bond_conf,
+    }
+}
+
+fn np_bond_mode_to_nmstate(np_mode: &nispor::BondMode) -> Option<BondMode> {
+    match np_mode {
+        nispor::BondMode::BalanceRoundRobin => Some(BondMode::RoundRobin),
+        nispor::BondMode::ActiveBackup => Some(BondMode::ActiveBackup),
+        nispor::BondMode::BalanceXor => Some(BondMode::Xor),
+        nispor::BondMode::Broadcast => Some(BondMode::Broadcast),
+        nispor::BondMode::Ieee8021AD => Some(BondMode::Lacp),
+        nispor::BondMode::BalanceTlb => Some(BondMode::Tlb),
+        nispor::BondMode::BalanceAlb => Some(BondMode::Alb),
+        _ => None,
+    }
+}
+
+pub(crate) fn nmstate_bond_mode_to_np(mode: &BondMode) -> nispor::BondMode {
+    match mode {
+        BondMode::RoundRobin => nispor::BondMode::BalanceRoundRobin,
+        BondMode::ActiveBackup => nispor::BondMode::ActiveBackup,
+        BondMode::Xor => nispor::BondMode::BalanceXor,
+        BondMode::Broadcast => nispor::BondMode::Broadcast,
+        BondMode::Lacp => nispor::BondMode::Ieee8021AD,
+        BondMode::Tlb => nispor::BondMode::BalanceTlb,
+        BondMode::Alb => nispor::BondMode::BalanceAlb,
+    }
+}
+
+fn np_bond_options_to_nmstate(np_bond: &nispor::BondInfo) -> BondOptions {
+    BondOptions {
+        miimon: np_bond.miimon,
+        updelay: np_bond.updelay,
+        downdelay: np_bond.downdelay,
+        arp_interval: np_bond.arp_interval,
+        arp_ip_target: np_bond.arp_ip_target.clone(),
+        primary: np_bond.primary.clone(),
+        min_links: np_bond.min_links,
+        lacp_rate: match np_bond.lacp_rate {
+            Some(nispor::BondLacpRate::Slow) => Some(BondLacpRate::Slow),
+            Some(nispor::BondLacpRate::Fast) => Some(BondLacpRate::Fast),
+            _ => None,
+        },
+        xmit_hash_policy: match np_bond.xmit_hash_policy {
+            Some(nispor::BondXmitHashPolicy::Layer2) => {
+                Some(BondXmitHashPolicy::Layer2)
+            }
+            Some(nispor::BondXmitHashPolicy::Layer34) => {
+                Some(BondXmitHashPolicy::Layer34)
+            }
+            Some(nispor::BondXmitHashPolicy::Layer23) => {
+                Some(BondXmitHashPolicy::Layer23)
+            }
+            Some(nispor::BondXmitHashPolicy::Encap23) => {
+                Some(BondXmitHashPolicy::Encap23)
+            }
+            Some(nispor::BondXmitHashPolicy::Encap34) => {
+                Some(BondXmitHashPolicy::Encap34)
+            }
+            _ => None,
+        },
+    }
+}
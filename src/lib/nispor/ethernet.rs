@@ -1,11 +1,34 @@
-use crate::{BaseInterface, EthernetInterface};
+use crate::{BaseInterface, EthernetConfig, EthernetDuplex, EthernetInterface};
 
 pub(crate) fn np_ethernet_to_nmstate(
-    _np_iface: nispor::Iface,
+    np_iface: nispor::Iface,
     base_iface: BaseInterface,
 ) -> EthernetInterface {
     EthernetInterface {
         base: base_iface,
-        ..Default::default()
+        ethernet: np_iface
+            .ethtool
+            .as_ref()
+            .and_then(|e| e.link_mode.as_ref())
+            .map(np_link_mode_to_nmstate),
+    }
+}
+
+// Kernel use 0 or u32::MAX for unknown speed when link is down
+fn np_link_mode_to_nmstate(
+    np_link_mode: &nispor::EthtoolLinkModeInfo,
+) -> EthernetConfig {
+    EthernetConfig {
+        auto_negotiation: Some(np_link_mode.auto_negotiate),
+        speed: if np_link_mode.speed == 0 || np_link_mode.speed == u32::MAX {
+            None
+        } else {
+            Some(np_link_mode.speed)
+        },
+        duplex: match np_link_mode.duplex {
+            nispor::EthtoolLinkModeDuplex::Full => Some(EthernetDuplex::Full),
+            nispor::EthtoolLinkModeDuplex::Half => Some(EthernetDuplex::Half),
+            _ => None,
+        },
     }
 }
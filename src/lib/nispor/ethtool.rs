@@ -0,0 +1,56 @@
+use crate::{
+    EthtoolCoalesceConfig, EthtoolConfig, EthtoolPauseConfig, EthtoolRingConfig,
+};
+
+pub(crate) fn np_ethtool_to_nmstate(
+    np_ethtool: &nispor::EthtoolInfo,
+) -> EthtoolConfig {
+    EthtoolConfig {
+        pause: np_ethtool.pause.as_ref().map(|p| EthtoolPauseConfig {
+            rx: Some(p.rx),
+            tx: Some(p.tx),
+            autoneg: Some(p.auto_negotiate),
+        }),
+        // Fixed features cannot be changed, hence not included
+        feature: np_ethtool
+            .features
+            .as_ref()
+            .map(|f| f.changeable.clone().into_iter().collect()),
+        coalesce: np_ethtool.coalesce.as_ref().map(np_coalesce_to_nmstate),
+        ring: np_ethtool.ring.as_ref().map(|r| EthtoolRingConfig {
+            rx: r.rx,
+            rx_jumbo: r.rx_jumbo,
+            rx_mini: r.rx_mini,
+            tx: r.tx,
+        }),
+    }
+}
+
+fn np_coalesce_to_nmstate(
+    np_coalesce: &nispor::EthtoolCoalesceInfo,
+) -> EthtoolCoalesceConfig {
+    EthtoolCoalesceConfig {
+        adaptive_rx: np_coalesce.adaptive_rx,
+        adaptive_tx: np_coalesce.adaptive_tx,
+        pkt_rate_high: np_coalesce.pkt_rate_high,
+        pkt_rate_low: np_coalesce.pkt_rate_low,
+        rate_sample_interval: np_coalesce.rate_sample_interval,
+        rx_frames: np_coalesce.rx_frames,
+        rx_frames_high: np_coalesce.rx_frames_high,
+        rx_frames_irq: np_coalesce.rx_frames_irq,
+        rx_frames_low: np_coalesce.rx_frames_low,
+        rx_usecs: np_coalesce.rx_usecs,
+        rx_usecs_high: np_coalesce.rx_usecs_high,
+        rx_usecs_irq: np_coalesce.rx_usecs_irq,
+        rx_usecs_low: np_coalesce.rx_usecs_low,
+        stats_block_usecs: np_coalesce.stats_block_usecs,
+        tx_frames: np_coalesce.tx_frames,
+        tx_frames_high: np_coalesce.tx_frames_high,
+        tx_frames_irq: np_coalesce.tx_frames_irq,
+        tx_frames_low: np_coalesce.tx_frames_low,
+        tx_usecs: np_coalesce.tx_usecs,
+        tx_usecs_high: np_coalesce.tx_usecs_high,
+        tx_usecs_irq: np_coalesce.tx_usecs_irq,
+        tx_usecs_low: np_coalesce.tx_usecs_low,
+    }
+}
@@ -1,4 +1,15 @@
-use crate::{InterfaceIpAddr, InterfaceIpv4, InterfaceIpv6};
+use log::warn;
+
+use crate::{
+    ip::IP_ADDR_LIFETIME_FOREVER, ErrorKind, InterfaceIpAddr, InterfaceIpv4,
+    InterfaceIpv6, Ipv6AddrGenMode, NmstateError,
+};
+
+const IPV6_SYSCTL_DIR: &str = "/proc/sys/net/ipv6/conf";
+
+// See `enum in6_addr_gen_mode` of linux kernel
+const KERNEL_ADDR_GEN_MODE_EUI64: &str = "0";
+const KERNEL_ADDR_GEN_MODE_STABLE_PRIVACY: &str = "2";
 
 pub(crate) fn np_ipv4_to_nmstate(
     np_iface: &nispor::Iface,
@@ -8,14 +19,15 @@ pub(crate) fn np_ipv4_to_nmstate(
         if np_ip.addresses.len() > 0 {
             ip.enabled = true;
         }
+        // Finite lifetime address could also be added by other tools, the
+        // DHCP state is left to the NM profile
         for np_addr in &np_ip.addresses {
-            if np_addr.valid_lft != "forever" {
-                ip.dhcp = true;
-            }
-            ip.addresses.push(InterfaceIpAddr {
-                ip: np_addr.address.clone(),
-                prefix_length: np_addr.prefix_len as u32,
-            });
+            ip.addresses.push(np_addr_to_nmstate(
+                &np_addr.address,
+                np_addr.prefix_len,
+                &np_addr.valid_lft,
+                &np_addr.preferred_lft,
+            ));
         }
         Some(ip)
     } else {
@@ -39,14 +51,44 @@ pub(crate) fn np_ipv6_to_nmstate(
         if np_ip.addresses.len() > 0 {
             ip.enabled = true;
         }
+        let mut has_dynamic_addr = false;
         for np_addr in &np_ip.addresses {
-            if np_addr.valid_lft != "forever" {
-                ip.autoconf = true;
+            if np_addr.valid_lft != IP_ADDR_LIFETIME_FOREVER {
+                has_dynamic_addr = true;
             }
-            ip.addresses.push(InterfaceIpAddr {
-                ip: np_addr.address.clone(),
-                prefix_length: np_addr.prefix_len as u32,
-            });
+            ip.addresses.push(np_addr_to_nmstate(
+                &np_addr.address,
+                np_addr.prefix_len,
+                &np_addr.valid_lft,
+                &np_addr.preferred_lft,
+            ));
+        }
+        ip.autoconf = match (
+            read_ipv6_sysctl(&np_iface.name, "accept_ra"),
+            read_ipv6_sysctl(&np_iface.name, "autoconf"),
+        ) {
+            (Some(accept_ra), Some(autoconf)) => is_kernel_autoconf_enabled(
+                &accept_ra,
+                &autoconf,
+                read_ipv6_sysctl(&np_iface.name, "forwarding").as_deref(),
+            ),
+            _ => has_dynamic_addr,
+        };
+        if ip.autoconf {
+            ip.addr_gen_mode =
+                match read_ipv6_sysctl(&np_iface.name, "addr_gen_mode")
+                    .as_deref()
+                {
+                    Some(KERNEL_ADDR_GEN_MODE_EUI64) => {
+                        Some(Ipv6AddrGenMode::Eui64)
+                    }
+                    Some(KERNEL_ADDR_GEN_MODE_STABLE_PRIVACY) => {
+                        Some(Ipv6AddrGenMode::StablePrivacy)
+                    }
+                    _ => None,
+                };
+            // Kernel use `::` for token not set
+            ip.token = np_ip.token.clone().filter(|t| t != "::");
         }
         Some(ip)
     } else {
@@ -62,12 +104,37 @@ pub(crate) fn np_ipv6_to_nmstate(
     }
 }
 
+// Lifetime is only stored for dynamic address
+fn np_addr_to_nmstate(
+    address: &str,
+    prefix_len: u8,
+    valid_lft: &str,
+    preferred_lft: &str,
+) -> InterfaceIpAddr {
+    let is_auto = valid_lft != IP_ADDR_LIFETIME_FOREVER;
+    InterfaceIpAddr {
+        ip: address.to_string(),
+        prefix_length: prefix_len as u32,
+        valid_life_time: if is_auto {
+            Some(valid_lft.to_string())
+        } else {
+            None
+        },
+        preferred_life_time: if is_auto {
+            Some(preferred_lft.to_string())
+        } else {
+            None
+        },
+    }
+}
+
 pub(crate) fn nmstate_ipv4_to_np(
     nms_ipv4: Option<&InterfaceIpv4>,
 ) -> nispor::IpConf {
     let mut np_ip_conf = nispor::IpConf::default();
     if let Some(nms_ipv4) = nms_ipv4 {
-        for nms_addr in &nms_ipv4.addresses {
+        // Dynamic addresses are maintained by DHCP or IPv6 autoconf
+        for nms_addr in nms_ipv4.addresses.iter().filter(|a| !a.is_auto()) {
             np_ip_conf.addresses.push(nispor::IpAddrConf {
                 address: nms_addr.ip.to_string(),
                 prefix_len: nms_addr.prefix_length as u8,
@@ -82,7 +149,8 @@ pub(crate) fn nmstate_ipv6_to_np(
 ) -> nispor::IpConf {
     let mut np_ip_conf = nispor::IpConf::default();
     if let Some(nms_ipv6) = nms_ipv6 {
-        for nms_addr in &nms_ipv6.addresses {
+        // Dynamic addresses are maintained by DHCP or IPv6 autoconf
+        for nms_addr in nms_ipv6.addresses.iter().filter(|a| !a.is_auto()) {
             np_ip_conf.addresses.push(nispor::IpAddrConf {
                 address: nms_addr.ip.to_string(),
                 prefix_len: nms_addr.prefix_length as u8,
@@ -91,3 +159,98 @@ pub(crate) fn nmstate_ipv6_to_np(
     }
     np_ip_conf
 }
+
+// Kernel does IPv6 autoconf only when both accept_ra and autoconf are
+// enabled, and ignores router advertisement when forwarding is enabled unless
+// accept_ra is 2.
+fn is_kernel_autoconf_enabled(
+    accept_ra: &str,
+    autoconf: &str,
+    forwarding: Option<&str>,
+) -> bool {
+    let ra_accepted = match accept_ra {
+        "0" => false,
+        "1" => forwarding != Some("1"),
+        _ => true,
+    };
+    ra_accepted && autoconf != "0"
+}
+
+// Nispor has no interface for these IPv6 sysctls yet. Like the rest of the
+// kernel only mode, they are not rolled back when applying fails later.
+pub(crate) fn nms_ipv6_sysctl_apply(
+    iface_name: &str,
+    nms_ipv6: &InterfaceIpv6,
+) -> Result<(), NmstateError> {
+    if !nms_ipv6.enabled {
+        return Ok(());
+    }
+    let accept_ra = if !nms_ipv6.autoconf {
+        "0"
+    } else if read_ipv6_sysctl(iface_name, "forwarding").as_deref() == Some("1")
+    {
+        "2"
+    } else {
+        "1"
+    };
+    write_ipv6_sysctl(iface_name, "accept_ra", accept_ra)?;
+    write_ipv6_sysctl(
+        iface_name,
+        "autoconf",
+        if nms_ipv6.autoconf { "1" } else { "0" },
+    )?;
+    if let Some(addr_gen_mode) = nms_ipv6.addr_gen_mode.as_ref() {
+        write_ipv6_sysctl(
+            iface_name,
+            "addr_gen_mode",
+            match addr_gen_mode {
+                Ipv6AddrGenMode::Eui64 => KERNEL_ADDR_GEN_MODE_EUI64,
+                Ipv6AddrGenMode::StablePrivacy => {
+                    KERNEL_ADDR_GEN_MODE_STABLE_PRIVACY
+                }
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn read_ipv6_sysctl(iface_name: &str, key: &str) -> Option<String> {
+    let path = format!("{}/{}/{}", IPV6_SYSCTL_DIR, iface_name, key);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => Some(content.trim().to_string()),
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to read {}: {}", path, e);
+            }
+            None
+        }
+    }
+}
+
+fn write_ipv6_sysctl(
+    iface_name: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), NmstateError> {
+    let path = format!("{}/{}/{}", IPV6_SYSCTL_DIR, iface_name, key);
+    std::fs::write(&path, value).or_else(|e| {
+        Err(NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Failed to write {} to {}: {}", value, path, e),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_autoconf_enabled() {
+        assert!(is_kernel_autoconf_enabled("1", "1", Some("0")));
+        assert!(is_kernel_autoconf_enabled("2", "1", Some("1")));
+        assert!(!is_kernel_autoconf_enabled("1", "1", Some("1")));
+        assert!(!is_kernel_autoconf_enabled("1", "0", Some("0")));
+        assert!(!is_kernel_autoconf_enabled("0", "1", None));
+    }
+}
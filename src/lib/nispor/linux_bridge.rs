@@ -1,38 +1,155 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use log::warn;
+
 use crate::{
-    BaseInterface, LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeOptions,
-    LinuxBridgePortConfig, LinuxBridgeStpOptions,
+    BaseInterface, LinuxBridgeConfig, LinuxBridgeInterface,
+    LinuxBridgeMulticastRouterType, LinuxBridgeOptions, LinuxBridgePortConfig,
+    LinuxBridgePortTrunkTag, LinuxBridgePortVlanConfig,
+    LinuxBridgePortVlanMode, LinuxBridgePortVlanRange, LinuxBridgeStpOptions,
+    VlanProtocol,
 };
 
+// Kernel is using 1/100 seconds(USER_HZ) for bridge timers
+const KERNEL_TIMER_UNITS_PER_SECOND: u32 = 100;
+
 pub(crate) fn np_bridge_to_nmstate(
     np_iface: nispor::Iface,
     base_iface: BaseInterface,
+    np_br_ports: &HashMap<String, LinuxBridgePortConfig>,
 ) -> LinuxBridgeInterface {
     LinuxBridgeInterface {
         base: base_iface,
         bridge: Some(LinuxBridgeConfig {
-            port: Some(np_bridge_ports_to_nmstate(&np_iface)),
+            port: Some(np_bridge_ports_to_nmstate(&np_iface, np_br_ports)),
             options: Some(np_bridge_options_to_nmstate(&np_iface)),
-            ..Default::default()
         }),
-        ..Default::default()
     }
 }
 
+// The per-port bridge settings are stored in the port interface, the
+// returned config is indexed by port name in `np_br_ports`.
+pub(crate) fn np_bridge_port_to_nmstate(
+    np_iface: &nispor::Iface,
+) -> Option<LinuxBridgePortConfig> {
+    let np_port_info = np_iface.bridge_port.as_ref()?;
+    Some(LinuxBridgePortConfig {
+        name: np_iface.name.to_string(),
+        stp_hairpin_mode: Some(np_port_info.hairpin_mode),
+        stp_path_cost: Some(np_port_info.stp_path_cost),
+        stp_priority: Some(np_port_info.stp_priority),
+        vlan: np_iface
+            .bridge_vlan
+            .as_deref()
+            .and_then(np_port_vlan_to_nmstate),
+    })
+}
+
 fn np_bridge_ports_to_nmstate(
     np_iface: &nispor::Iface,
+    np_br_ports: &HashMap<String, LinuxBridgePortConfig>,
 ) -> Vec<LinuxBridgePortConfig> {
     let mut ports = Vec::new();
     if let Some(np_bridge) = &np_iface.bridge {
+        let vlan_filtering = np_bridge.vlan_filtering == Some(true);
         for port_iface_name in &np_bridge.ports {
-            ports.push(LinuxBridgePortConfig {
-                name: port_iface_name.to_string(),
-                ..Default::default()
-            });
+            let mut port_conf = match np_br_ports.get(port_iface_name) {
+                Some(port_conf) => port_conf.clone(),
+                None => LinuxBridgePortConfig {
+                    name: port_iface_name.to_string(),
+                    ..Default::default()
+                },
+            };
+            if !vlan_filtering {
+                port_conf.vlan = None;
+            }
+            ports.push(port_conf);
         }
     }
     ports
 }
 
+// A port with only one PVID and egress untagged VLAN is treated as access
+// port, otherwise trunk port with that VLAN as native VLAN.
+// Kernel never put PVID into a VLAN range, but egress untagged VLAN range
+// is possible while nmstate can only express untagged VLAN as native VLAN.
+fn np_port_vlan_to_nmstate(
+    np_vlan_entries: &[nispor::BridgeVlanEntry],
+) -> Option<LinuxBridgePortVlanConfig> {
+    let mut native_tag = None;
+    let mut trunk_tags = Vec::new();
+    for np_vlan_entry in np_vlan_entries {
+        let (min, max) = match (np_vlan_entry.vid_range, np_vlan_entry.vid) {
+            (Some((min, max)), _) => (min, max),
+            (None, Some(vid)) => (vid, vid),
+            (None, None) => continue,
+        };
+        if min == max
+            && np_vlan_entry.is_pvid
+            && np_vlan_entry.is_egress_untagged
+            && native_tag.is_none()
+        {
+            native_tag = Some(min);
+            continue;
+        }
+        if np_vlan_entry.is_pvid || np_vlan_entry.is_egress_untagged {
+            warn!(
+                "Treating bridge port VLAN {}-{} with PVID {} egress \
+                untagged {} as tagged trunk VLAN",
+                min,
+                max,
+                np_vlan_entry.is_pvid,
+                np_vlan_entry.is_egress_untagged
+            );
+        }
+        trunk_tags.push(if min == max {
+            LinuxBridgePortTrunkTag::Id(min)
+        } else {
+            LinuxBridgePortTrunkTag::IdRange(LinuxBridgePortVlanRange {
+                min,
+                max,
+            })
+        });
+    }
+    if trunk_tags.is_empty() {
+        native_tag.map(|tag| LinuxBridgePortVlanConfig {
+            mode: Some(LinuxBridgePortVlanMode::Access),
+            tag: Some(tag),
+            ..Default::default()
+        })
+    } else {
+        Some(LinuxBridgePortVlanConfig {
+            mode: Some(LinuxBridgePortVlanMode::Trunk),
+            enable_native: Some(native_tag.is_some()),
+            tag: native_tag,
+            trunk_tags: Some(trunk_tags),
+        })
+    }
+}
+
+pub(crate) fn nms_port_vlan_conf_to_np(
+    vlan_conf: &LinuxBridgePortVlanConfig,
+) -> Vec<nispor::BridgeVlanEntry> {
+    vlan_conf
+        .to_vlan_ranges()
+        .into_iter()
+        .map(|(min, max, is_pvid, is_egress_untagged)| {
+            let (vid, vid_range) = if min == max {
+                (Some(min), None)
+            } else {
+                (None, Some((min, max)))
+            };
+            nispor::BridgeVlanEntry {
+                vid,
+                vid_range,
+                is_pvid,
+                is_egress_untagged,
+            }
+        })
+        .collect()
+}
+
 fn np_bridge_options_to_nmstate(
     np_iface: &nispor::Iface,
 ) -> LinuxBridgeOptions {
@@ -46,7 +163,203 @@ fn np_bridge_options_to_nmstate(
                 ]
                 .contains(&np_bridge.stp_state),
             ),
+            forward_delay: np_bridge.forward_delay.map(kernel_timer_to_sec),
+            hello_time: np_bridge.hello_time.map(kernel_timer_to_sec),
+            max_age: np_bridge.max_age.map(kernel_timer_to_sec),
+            priority: np_bridge.priority,
         });
+        options.group_forward_mask = np_bridge.group_fwd_mask;
+        options.hash_max = np_bridge.hash_max;
+        options.mac_ageing_time = np_bridge
+            .ageing_time
+            .map(|t| t / KERNEL_TIMER_UNITS_PER_SECOND);
+        options.multicast_last_member_count =
+            np_bridge.multicast_last_member_count;
+        options.multicast_last_member_interval =
+            np_bridge.multicast_last_member_interval;
+        options.multicast_membership_interval =
+            np_bridge.multicast_membership_interval;
+        options.multicast_querier = np_bridge.multicast_querier;
+        options.multicast_querier_interval =
+            np_bridge.multicast_querier_interval;
+        options.multicast_query_interval = np_bridge.multicast_query_interval;
+        options.multicast_query_response_interval =
+            np_bridge.multicast_query_response_interval;
+        options.multicast_query_use_ifaddr =
+            np_bridge.multicast_query_use_ifaddr;
+        options.multicast_router = match np_bridge.multicast_router {
+            Some(nispor::BridgePortMulticastRouterType::Disabled) => {
+                Some(LinuxBridgeMulticastRouterType::Disabled)
+            }
+            Some(nispor::BridgePortMulticastRouterType::TempQuery) => {
+                Some(LinuxBridgeMulticastRouterType::Auto)
+            }
+            Some(nispor::BridgePortMulticastRouterType::Perm) => {
+                Some(LinuxBridgeMulticastRouterType::Enabled)
+            }
+            _ => None,
+        };
+        options.multicast_snooping = np_bridge.multicast_snooping;
+        options.multicast_startup_query_count =
+            np_bridge.multicast_startup_query_count;
+        options.multicast_startup_query_interval =
+            np_bridge.multicast_startup_query_interval;
+        options.vlan_filtering = np_bridge.vlan_filtering;
+        options.vlan_protocol = match np_bridge.vlan_protocol {
+            Some(nispor::BridgeVlanProtocol::Ieee8021Q) => {
+                Some(VlanProtocol::Ieee8021Q)
+            }
+            Some(nispor::BridgeVlanProtocol::Ieee8021AD) => {
+                Some(VlanProtocol::Ieee8021Ad)
+            }
+            _ => None,
+        };
     }
     options
 }
+
+// Saturate instead of truncating timer larger than u8::MAX seconds
+fn kernel_timer_to_sec(timer: u32) -> u8 {
+    u8::try_from(timer / KERNEL_TIMER_UNITS_PER_SECOND).unwrap_or(u8::MAX)
+}
+
+pub(crate) fn nms_bridge_conf_to_np(
+    br_conf: &LinuxBridgeConfig,
+) -> nispor::BridgeConf {
+    let mut np_br_conf = if let Some(opts) = &br_conf.options {
+        nms_bridge_options_to_np(opts)
+    } else {
+        nispor::BridgeConf::default()
+    };
+    np_br_conf.vlan_filtering = br_conf.vlan_filtering();
+    np_br_conf
+}
+
+fn nms_bridge_options_to_np(opts: &LinuxBridgeOptions) -> nispor::BridgeConf {
+    let stp = opts.stp.as_ref();
+    nispor::BridgeConf {
+        stp_state: stp.and_then(|stp| stp.enabled).map(|enabled| {
+            if enabled {
+                nispor::BridgeStpState::KernelStp
+            } else {
+                nispor::BridgeStpState::Disabled
+            }
+        }),
+        forward_delay: stp
+            .and_then(|stp| stp.forward_delay)
+            .map(sec_to_kernel_timer),
+        hello_time: stp.and_then(|stp| stp.hello_time).map(sec_to_kernel_timer),
+        max_age: stp.and_then(|stp| stp.max_age).map(sec_to_kernel_timer),
+        priority: stp.and_then(|stp| stp.priority),
+        ageing_time: opts
+            .mac_ageing_time
+            .map(|t| t * KERNEL_TIMER_UNITS_PER_SECOND),
+        group_fwd_mask: opts.group_forward_mask,
+        hash_max: opts.hash_max,
+        multicast_snooping: opts.multicast_snooping,
+        multicast_router: opts.multicast_router.map(|r| match r {
+            LinuxBridgeMulticastRouterType::Disabled => {
+                nispor::BridgePortMulticastRouterType::Disabled
+            }
+            LinuxBridgeMulticastRouterType::Auto => {
+                nispor::BridgePortMulticastRouterType::TempQuery
+            }
+            LinuxBridgeMulticastRouterType::Enabled => {
+                nispor::BridgePortMulticastRouterType::Perm
+            }
+        }),
+        multicast_querier: opts.multicast_querier,
+        multicast_query_use_ifaddr: opts.multicast_query_use_ifaddr,
+        multicast_last_member_count: opts.multicast_last_member_count,
+        multicast_last_member_interval: opts.multicast_last_member_interval,
+        multicast_membership_interval: opts.multicast_membership_interval,
+        multicast_querier_interval: opts.multicast_querier_interval,
+        multicast_query_interval: opts.multicast_query_interval,
+        multicast_query_response_interval: opts
+            .multicast_query_response_interval,
+        multicast_startup_query_count: opts.multicast_startup_query_count,
+        multicast_startup_query_interval: opts.multicast_startup_query_interval,
+        vlan_protocol: opts.vlan_protocol.map(|p| match p {
+            VlanProtocol::Ieee8021Q => nispor::BridgeVlanProtocol::Ieee8021Q,
+            VlanProtocol::Ieee8021Ad => nispor::BridgeVlanProtocol::Ieee8021AD,
+        }),
+        ..Default::default()
+    }
+}
+
+fn sec_to_kernel_timer(sec: u8) -> u32 {
+    u32::from(sec) * KERNEL_TIMER_UNITS_PER_SECOND
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gen_entry(
+        vid: Option<u16>,
+        vid_range: Option<(u16, u16)>,
+        is_pvid: bool,
+        is_egress_untagged: bool,
+    ) -> nispor::BridgeVlanEntry {
+        nispor::BridgeVlanEntry {
+            vid,
+            vid_range,
+            is_pvid,
+            is_egress_untagged,
+        }
+    }
+
+    #[test]
+    fn test_np_port_vlan_access() {
+        let vlan_conf =
+            np_port_vlan_to_nmstate(&[gen_entry(Some(100), None, true, true)])
+                .unwrap();
+        assert_eq!(vlan_conf.mode, Some(LinuxBridgePortVlanMode::Access));
+        assert_eq!(vlan_conf.tag, Some(100));
+        assert_eq!(vlan_conf.trunk_tags, None);
+    }
+
+    #[test]
+    fn test_np_port_vlan_trunk_with_range() {
+        let vlan_conf = np_port_vlan_to_nmstate(&[
+            gen_entry(Some(1), None, true, true),
+            gen_entry(None, Some((200, 299)), false, false),
+            gen_entry(None, Some((300, 300)), false, false),
+        ])
+        .unwrap();
+        assert_eq!(vlan_conf.mode, Some(LinuxBridgePortVlanMode::Trunk));
+        assert_eq!(vlan_conf.enable_native, Some(true));
+        assert_eq!(vlan_conf.tag, Some(1));
+        assert_eq!(
+            vlan_conf.trunk_tags,
+            Some(vec![
+                LinuxBridgePortTrunkTag::IdRange(LinuxBridgePortVlanRange {
+                    min: 200,
+                    max: 299
+                }),
+                LinuxBridgePortTrunkTag::Id(300),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_np_port_vlan_trunk_tagged_pvid() {
+        let vlan_conf =
+            np_port_vlan_to_nmstate(&[gen_entry(Some(100), None, true, false)])
+                .unwrap();
+        assert_eq!(vlan_conf.mode, Some(LinuxBridgePortVlanMode::Trunk));
+        assert_eq!(vlan_conf.enable_native, Some(false));
+        assert_eq!(vlan_conf.tag, None);
+    }
+
+    #[test]
+    fn test_np_port_vlan_empty() {
+        assert_eq!(np_port_vlan_to_nmstate(&[]), None);
+    }
+
+    #[test]
+    fn test_kernel_timer_saturate() {
+        assert_eq!(kernel_timer_to_sec(1500), 15);
+        assert_eq!(kernel_timer_to_sec(u32::MAX), u8::MAX);
+    }
+}
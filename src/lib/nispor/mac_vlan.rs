@@ -0,0 +1,67 @@
+use crate::{
+    BaseInterface, MacVlanConfig, MacVlanInterface, MacVlanMode, MacVtapConfig,
+    MacVtapInterface,
+};
+
+// Kernel IFLA_MACVLAN_FLAGS bit for disabling promiscuous mode
+const MACVLAN_FLAG_NOPROMISC: u16 = 1;
+
+pub(crate) fn np_mac_vlan_to_nmstate(
+    np_iface: nispor::Iface,
+    base_iface: BaseInterface,
+) -> MacVlanInterface {
+    let mac_vlan_conf =
+        np_iface.mac_vlan.map(|np_mac_vlan_info| MacVlanConfig {
+            base_iface: np_mac_vlan_info.base_iface,
+            mode: np_mac_vlan_mode_to_nmstate(&np_mac_vlan_info.mode),
+            promiscuous: Some(
+                np_mac_vlan_info.flags & MACVLAN_FLAG_NOPROMISC == 0,
+            ),
+        });
+    MacVlanInterface {
+        base: base_iface,
+        mac_vlan: mac_vlan_conf,
+    }
+}
+
+pub(crate) fn np_mac_vtap_to_nmstate(
+    np_iface: nispor::Iface,
+    base_iface: BaseInterface,
+) -> MacVtapInterface {
+    let mac_vtap_conf =
+        np_iface.mac_vtap.map(|np_mac_vtap_info| MacVtapConfig {
+            base_iface: np_mac_vtap_info.base_iface,
+            mode: np_mac_vlan_mode_to_nmstate(&np_mac_vtap_info.mode),
+            promiscuous: Some(
+                np_mac_vtap_info.flags & MACVLAN_FLAG_NOPROMISC == 0,
+            ),
+        });
+    MacVtapInterface {
+        base: base_iface,
+        mac_vtap: mac_vtap_conf,
+    }
+}
+
+fn np_mac_vlan_mode_to_nmstate(np_mode: &nispor::MacVlanMode) -> MacVlanMode {
+    match np_mode {
+        nispor::MacVlanMode::Vepa => MacVlanMode::Vepa,
+        nispor::MacVlanMode::Bridge => MacVlanMode::Bridge,
+        nispor::MacVlanMode::Private => MacVlanMode::Private,
+        nispor::MacVlanMode::PassThrough => MacVlanMode::Passthru,
+        nispor::MacVlanMode::Source => MacVlanMode::Source,
+        _ => MacVlanMode::Unknown,
+    }
+}
+
+pub(crate) fn nmstate_mac_vlan_mode_to_np(
+    mode: &MacVlanMode,
+) -> nispor::MacVlanMode {
+    match mode {
+        MacVlanMode::Vepa => nispor::MacVlanMode::Vepa,
+        MacVlanMode::Bridge => nispor::MacVlanMode::Bridge,
+        MacVlanMode::Private => nispor::MacVlanMode::Private,
+        MacVlanMode::Passthru => nispor::MacVlanMode::PassThrough,
+        MacVlanMode::Source => nispor::MacVlanMode::Source,
+        MacVlanMode::Unknown => nispor::MacVlanMode::Unknown,
+    }
+}
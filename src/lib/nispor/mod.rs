@@ -1,11 +1,19 @@
 mod base_iface;
+mod bond;
 mod error;
 mod ethernet;
+mod ethtool;
 mod ip;
 mod linux_bridge;
+mod mac_vlan;
+mod route;
+mod route_rule;
 mod show;
 mod apply;
 mod veth;
+mod vlan;
+mod vrf;
+mod vxlan;
 
 pub(crate) use show::nispor_retrieve;
 pub(crate) use apply::nispor_apply;
@@ -0,0 +1,110 @@
+use std::convert::TryFrom;
+
+use log::warn;
+
+use crate::{ErrorKind, NmstateError, RouteEntry, Routes};
+
+const SUPPORTED_ROUTE_SCOPE: [nispor::RouteScope; 2] =
+    [nispor::RouteScope::Universe, nispor::RouteScope::Link];
+
+// Routes created by kernel, DHCP or IPv6 router advertisement are not treated
+// as route config.
+const SUPPORTED_STATIC_ROUTE_PROTOCOL: [nispor::RouteProtocol; 2] =
+    [nispor::RouteProtocol::Boot, nispor::RouteProtocol::Static];
+
+const LOCAL_ROUTE_TABLE: u32 = 255;
+
+pub(crate) fn np_routes_to_nmstate(np_routes: &[nispor::Route]) -> Routes {
+    let mut running_routes = Vec::new();
+    let mut config_routes = Vec::new();
+    for np_route in np_routes.iter().filter(|np_route| {
+        SUPPORTED_ROUTE_SCOPE.contains(&np_route.scope)
+            && np_route.route_type == nispor::RouteType::Unicast
+            && np_route.table != LOCAL_ROUTE_TABLE
+            && np_route.oif.is_some()
+    }) {
+        let route = np_route_to_nmstate(np_route);
+        if SUPPORTED_STATIC_ROUTE_PROTOCOL.contains(&np_route.protocol) {
+            config_routes.push(route.clone());
+        }
+        running_routes.push(route);
+    }
+    Routes {
+        running: Some(running_routes),
+        config: Some(config_routes),
+    }
+}
+
+fn np_route_to_nmstate(np_route: &nispor::Route) -> RouteEntry {
+    let destination = match &np_route.dst {
+        Some(dst) => Some(dst.to_string()),
+        None => match np_route.address_family {
+            nispor::AddressFamily::IPv4 => Some("0.0.0.0/0".to_string()),
+            nispor::AddressFamily::IPv6 => Some("::/0".to_string()),
+            _ => {
+                warn!("Route {:?} is holding unknown IP family", np_route);
+                None
+            }
+        },
+    };
+    RouteEntry {
+        destination,
+        next_hop_iface: np_route.oif.clone(),
+        next_hop_addr: np_route
+            .via
+            .clone()
+            .or_else(|| np_route.gateway.clone()),
+        metric: np_route.metric,
+        table_id: Some(np_route.table),
+        ..Default::default()
+    }
+}
+
+// Generate nispor route config for routes been removed or added by the
+// merged routes.
+pub(crate) fn nms_routes_to_np(
+    merged_routes: &[RouteEntry],
+    cur_routes: &[RouteEntry],
+) -> Result<Vec<nispor::RouteConf>, NmstateError> {
+    let mut np_routes = Vec::new();
+    for route in cur_routes.iter().filter(|r| !merged_routes.contains(r)) {
+        let mut np_route = nms_route_to_np(route)?;
+        np_route.remove = true;
+        np_routes.push(np_route);
+    }
+    for route in merged_routes
+        .iter()
+        .filter(|r| !cur_routes.iter().any(|cur_route| r.is_match(cur_route)))
+    {
+        np_routes.push(nms_route_to_np(route)?);
+    }
+    Ok(np_routes)
+}
+
+fn nms_route_to_np(
+    route: &RouteEntry,
+) -> Result<nispor::RouteConf, NmstateError> {
+    let table = match route.table_id {
+        Some(table_id) => Some(u8::try_from(table_id).or_else(|_| {
+            Err(NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "Route table ID larger than {} is not supported by \
+                    nispor plugin yet: {}",
+                    u8::MAX,
+                    route
+                ),
+            ))
+        })?),
+        None => None,
+    };
+    Ok(nispor::RouteConf {
+        dst: route.destination.clone().unwrap_or_default(),
+        oif: route.next_hop_iface.clone(),
+        via: route.next_hop_addr.clone(),
+        metric: route.metric,
+        table,
+        protocol: Some(nispor::RouteProtocol::Static),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,119 @@
+use log::warn;
+
+use crate::{RouteRuleAction, RouteRuleEntry, RouteRuleFamily, RouteRules};
+
+// Default rules created by kernel for local, main and default route table
+const KERNEL_DEFAULT_RULES: [(u32, u32); 3] =
+    [(0, 255), (32766, 254), (32767, 253)];
+
+pub(crate) fn np_rules_to_nmstate(
+    np_rules: &[nispor::RouteRule],
+) -> RouteRules {
+    let mut rules = Vec::new();
+    for np_rule in np_rules {
+        if is_kernel_default_rule(np_rule) {
+            continue;
+        }
+        let family = match np_rule.address_family {
+            nispor::AddressFamily::IPv4 => RouteRuleFamily::Ipv4,
+            nispor::AddressFamily::IPv6 => RouteRuleFamily::Ipv6,
+            _ => {
+                warn!("Route rule {:?} is holding unknown IP family", np_rule);
+                continue;
+            }
+        };
+        let action = match np_rule.action {
+            nispor::RuleAction::Table => None,
+            nispor::RuleAction::Blackhole => Some(RouteRuleAction::Blackhole),
+            nispor::RuleAction::Unreachable => {
+                Some(RouteRuleAction::Unreachable)
+            }
+            nispor::RuleAction::Prohibit => Some(RouteRuleAction::Prohibit),
+            _ => {
+                warn!("Unsupported route rule action {:?}", np_rule.action);
+                continue;
+            }
+        };
+        rules.push(RouteRuleEntry {
+            family: Some(family),
+            ip_from: np_rule.src.clone(),
+            ip_to: np_rule.dst.clone(),
+            priority: np_rule.priority,
+            route_table: if action.is_none() {
+                np_rule.table
+            } else {
+                None
+            },
+            iif: np_rule.iif.clone(),
+            fwmark: np_rule.fw_mark,
+            fwmask: np_rule.fw_mask,
+            action,
+            ..Default::default()
+        });
+    }
+    RouteRules {
+        config: Some(rules),
+    }
+}
+
+fn is_kernel_default_rule(np_rule: &nispor::RouteRule) -> bool {
+    if let (Some(priority), Some(table)) = (np_rule.priority, np_rule.table) {
+        KERNEL_DEFAULT_RULES.contains(&(priority, table))
+            && np_rule.src.is_none()
+            && np_rule.dst.is_none()
+            && np_rule.iif.is_none()
+    } else {
+        false
+    }
+}
+
+// Generate nispor route rule config for rules been removed or added by the
+// merged rules.
+pub(crate) fn nms_rules_to_np(
+    merged_rules: &[RouteRuleEntry],
+    cur_rules: &[RouteRuleEntry],
+) -> Vec<nispor::RouteRuleConf> {
+    let mut np_rules = Vec::new();
+    for rule in cur_rules.iter().filter(|r| !merged_rules.contains(r)) {
+        let mut np_rule = nms_rule_to_np(rule);
+        np_rule.remove = true;
+        np_rules.push(np_rule);
+    }
+    for rule in merged_rules
+        .iter()
+        .filter(|r| !cur_rules.iter().any(|cur_rule| r.is_match(cur_rule)))
+    {
+        np_rules.push(nms_rule_to_np(rule));
+    }
+    np_rules
+}
+
+fn nms_rule_to_np(rule: &RouteRuleEntry) -> nispor::RouteRuleConf {
+    nispor::RouteRuleConf {
+        action: Some(match rule.action {
+            None => nispor::RuleAction::Table,
+            Some(RouteRuleAction::Blackhole) => nispor::RuleAction::Blackhole,
+            Some(RouteRuleAction::Unreachable) => {
+                nispor::RuleAction::Unreachable
+            }
+            Some(RouteRuleAction::Prohibit) => nispor::RuleAction::Prohibit,
+        }),
+        address_family: Some(if rule.is_ipv6() {
+            nispor::AddressFamily::IPv6
+        } else {
+            nispor::AddressFamily::IPv4
+        }),
+        table: if rule.action.is_none() {
+            rule.route_table
+        } else {
+            None
+        },
+        src: rule.ip_from.clone(),
+        dst: rule.ip_to.clone(),
+        iif: rule.iif.clone(),
+        priority: rule.priority,
+        fw_mark: rule.fwmark,
+        fw_mask: rule.fwmask,
+        ..Default::default()
+    }
+}
@@ -1,19 +1,39 @@
+use std::collections::HashMap;
+
 use log::{debug, warn};
 
 use crate::{
     nispor::{
-        base_iface::np_iface_to_base_iface, error::np_error_to_nmstate,
-        ethernet::np_ethernet_to_nmstate, linux_bridge::np_bridge_to_nmstate,
+        base_iface::np_iface_to_base_iface,
+        bond::np_bond_to_nmstate,
+        error::np_error_to_nmstate,
+        ethernet::np_ethernet_to_nmstate,
+        linux_bridge::{np_bridge_port_to_nmstate, np_bridge_to_nmstate},
+        mac_vlan::{np_mac_vlan_to_nmstate, np_mac_vtap_to_nmstate},
+        route::np_routes_to_nmstate,
+        route_rule::np_rules_to_nmstate,
         veth::np_veth_to_nmstate,
+        vlan::np_vlan_to_nmstate,
+        vrf::np_vrf_to_nmstate,
+        vxlan::np_vxlan_to_nmstate,
     },
-    Interface, InterfaceType, NetworkState, NmstateError, UnknownInterface,
+    DummyInterface, Interface, InterfaceType, LinuxBridgePortConfig,
+    NetworkState, NmstateError, OvsInterface, UnknownInterface,
 };
 
 pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
     let mut net_state = NetworkState::new();
     net_state.prop_list.push("interfaces");
+    net_state.prop_list.push("routes");
+    net_state.prop_list.push("route_rules");
     let mut np_state = nispor::NetState::retrieve()
         .or_else(|ref np_error| Err(np_error_to_nmstate(np_error)))?;
+    let np_br_ports: HashMap<String, LinuxBridgePortConfig> = np_state
+        .ifaces
+        .values()
+        .filter_map(np_bridge_port_to_nmstate)
+        .map(|port_conf| (port_conf.name.to_string(), port_conf))
+        .collect();
     for (_, np_iface) in np_state.ifaces.drain() {
         debug!(
             "Got nispor interface name {} type {:?}",
@@ -22,7 +42,7 @@ pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
         let base_iface = np_iface_to_base_iface(&np_iface);
         let iface = match &base_iface.iface_type {
             InterfaceType::LinuxBridge => Interface::LinuxBridge(
-                np_bridge_to_nmstate(np_iface, base_iface),
+                np_bridge_to_nmstate(np_iface, base_iface, &np_br_ports),
             ),
             InterfaceType::Ethernet => Interface::Ethernet(
                 np_ethernet_to_nmstate(np_iface, base_iface),
@@ -30,6 +50,30 @@ pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
             InterfaceType::Veth => {
                 Interface::Veth(np_veth_to_nmstate(np_iface, base_iface))
             }
+            InterfaceType::Bond => {
+                Interface::Bond(np_bond_to_nmstate(np_iface, base_iface))
+            }
+            InterfaceType::Dummy => {
+                Interface::Dummy(DummyInterface::new(base_iface))
+            }
+            InterfaceType::Vlan => {
+                Interface::Vlan(np_vlan_to_nmstate(np_iface, base_iface))
+            }
+            InterfaceType::Vrf => {
+                Interface::Vrf(np_vrf_to_nmstate(np_iface, base_iface))
+            }
+            InterfaceType::Vxlan => {
+                Interface::Vxlan(np_vxlan_to_nmstate(np_iface, base_iface))
+            }
+            InterfaceType::MacVlan => {
+                Interface::MacVlan(np_mac_vlan_to_nmstate(np_iface, base_iface))
+            }
+            InterfaceType::MacVtap => {
+                Interface::MacVtap(np_mac_vtap_to_nmstate(np_iface, base_iface))
+            }
+            InterfaceType::OvsInterface => {
+                Interface::OvsInterface(OvsInterface::new(base_iface))
+            }
             InterfaceType::Loopback | InterfaceType::Tun => {
                 // Nmstate has no plan on supporting loopback/tun interface
                 continue;
@@ -44,5 +88,7 @@ pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
         };
         net_state.append_interface_data(iface);
     }
+    net_state.routes = np_routes_to_nmstate(&np_state.routes);
+    net_state.route_rules = np_rules_to_nmstate(&np_state.rules);
     Ok(net_state)
 }
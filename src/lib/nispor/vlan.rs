@@ -0,0 +1,20 @@
+use crate::{BaseInterface, VlanConfig, VlanInterface, VlanProtocol};
+
+pub(crate) fn np_vlan_to_nmstate(
+    np_iface: nispor::Iface,
+    base_iface: BaseInterface,
+) -> VlanInterface {
+    let vlan_conf = np_iface.vlan.map(|np_vlan_info| VlanConfig {
+        base_iface: np_vlan_info.base_iface.to_string(),
+        id: np_vlan_info.vlan_id,
+        protocol: match np_vlan_info.protocol {
+            nispor::VlanProtocol::Ieee8021Q => Some(VlanProtocol::Ieee8021Q),
+            nispor::VlanProtocol::Ieee8021AD => Some(VlanProtocol::Ieee8021Ad),
+            _ => None,
+        },
+    });
+    VlanInterface {
+        base: base_iface,
+        vlan: vlan_conf,
+    }
+}
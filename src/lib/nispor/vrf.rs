@@ -0,0 +1,15 @@
+use crate::{BaseInterface, VrfConfig, VrfInterface};
+
+pub(crate) fn np_vrf_to_nmstate(
+    np_iface: nispor::Iface,
+    base_iface: BaseInterface,
+) -> VrfInterface {
+    let vrf_conf = np_iface.vrf.map(|np_vrf_info| VrfConfig {
+        port: Some(np_vrf_info.subordinates),
+        route_table_id: np_vrf_info.table_id,
+    });
+    VrfInterface {
+        base: base_iface,
+        vrf: vrf_conf,
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{BaseInterface, VxlanConfig, VxlanInterface};
+
+pub(crate) fn np_vxlan_to_nmstate(
+    np_iface: nispor::Iface,
+    base_iface: BaseInterface,
+) -> VxlanInterface {
+    let vxlan_conf = np_iface.vxlan.map(|np_vxlan_info| VxlanConfig {
+        id: np_vxlan_info.vxlan_id,
+        base_iface: non_empty_string(np_vxlan_info.base_iface),
+        remote: non_empty_string(np_vxlan_info.remote),
+        local: non_empty_string(np_vxlan_info.local),
+        destination_port: Some(np_vxlan_info.dst_port),
+        learning: Some(np_vxlan_info.learning),
+    });
+    VxlanInterface {
+        base: base_iface,
+        vxlan: vxlan_conf,
+    }
+}
+
+// Nispor use empty string for unset remote, local and base interface
+fn non_empty_string(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
@@ -1,17 +1,36 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 
 use log::warn;
 
 use nm_dbus::{
-    NmApi, NmConnection, NmSettingBridge, NmSettingConnection, NmSettingIp,
-    NmSettingIpMethod,
+    NmApi, NmConnection, NmSettingBond, NmSettingBridge, NmSettingBridgePort,
+    NmSettingBridgeVlanRange, NmSettingConnection, NmSettingIp,
+    NmSettingIpMethod, NmSettingMacVlan, NmSettingVeth, NmSettingVlan,
+    NmSettingVrf, NmSettingVxlan, NmSettingWired,
 };
 
 use crate::{
     nm::checkpoint::nm_checkpoint_timeout_extend,
-    nm::error::nm_error_to_nmstate, ErrorKind, Interface, InterfaceIpv4,
-    InterfaceIpv6, InterfaceType, LinuxBridgeConfig, LinuxBridgeOptions,
-    LinuxBridgeStpOptions, NetworkState, NmstateError,
+    nm::dns::{
+        copy_nm_dns, dns_conf_to_nm, purge_dns_from_other_conns, NmDnsConf,
+    },
+    nm::error::nm_error_to_nmstate,
+    nm::ethtool::ethtool_conf_to_nm,
+    nm::ip::nms_addr_gen_mode_to_nm,
+    nm::ovs::{
+        gen_nm_ovs_port_conns, get_ovs_port_name, get_stale_ovs_port_uuids,
+        ovs_bridge_conf_to_nm, ovs_iface_to_nm,
+        NM_SETTING_OVS_PORT_SETTING_NAME,
+    },
+    nm::route::gen_nm_ip_routes,
+    nm::route_rule::gen_nm_ip_rules,
+    nm::show::NM_SETTING_WIRED_SETTING_NAME,
+    BaseInterface, BondConfig, ErrorKind, EthernetConfig, Interface,
+    InterfaceIpAddr, InterfaceIpv4, InterfaceIpv6, InterfaceType,
+    LinuxBridgeConfig, LinuxBridgePortConfig, MacVlanMode, NetworkState,
+    NmstateError, RouteEntry, RouteRuleEntry, VlanConfig, VlanProtocol,
+    VrfConfig, VxlanConfig,
 };
 
 // We only adjust timeout for every 20 profile addtions.
@@ -23,15 +42,71 @@ pub(crate) fn nm_apply(
     add_net_state: &NetworkState,
     chg_net_state: &NetworkState,
     del_net_state: &NetworkState,
-    _cur_net_state: &NetworkState,
+    cur_net_state: &NetworkState,
     checkpoint: &str,
 ) -> Result<(), NmstateError> {
     let nm_api = NmApi::new()
         .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
 
-    apply_single_state(&nm_api, del_net_state, checkpoint)?;
-    apply_single_state(&nm_api, add_net_state, checkpoint)?;
-    apply_single_state(&nm_api, chg_net_state, checkpoint)?;
+    // Linux bridge port settings are stored in port profiles, while the
+    // port and its controller might be in different state.
+    let mut br_port_confs = add_net_state.interfaces.get_br_port_confs();
+    br_port_confs.extend(chg_net_state.interfaces.get_br_port_confs());
+
+    for iface in add_net_state
+        .interfaces
+        .to_vec()
+        .into_iter()
+        .chain(chg_net_state.interfaces.to_vec())
+    {
+        if iface.is_up() {
+            check_link_conf_without_wired(
+                iface,
+                cur_net_state
+                    .interfaces
+                    .get_iface(iface.name(), iface.iface_type()),
+            )?;
+        }
+    }
+
+    // The full route and route rule config are stored in chg_net_state
+    let routes = chg_net_state.routes.config.as_deref().unwrap_or(&[]);
+    let rules = chg_net_state.route_rules.config.as_deref().unwrap_or(&[]);
+    let nm_dns_conf = match chg_net_state.dns.config.as_ref() {
+        Some(dns_conf) => Some(NmDnsConf::new(
+            dns_conf,
+            routes,
+            &[
+                &del_net_state.interfaces,
+                &add_net_state.interfaces,
+                &chg_net_state.interfaces,
+                &cur_net_state.interfaces,
+            ],
+            cur_net_state.routes.running.as_deref().unwrap_or(&[]),
+        )?),
+        None => None,
+    };
+
+    let mut applied_uuids: Vec<String> = Vec::new();
+    for net_state in &[del_net_state, add_net_state, chg_net_state] {
+        applied_uuids.extend(apply_single_state(
+            &nm_api,
+            net_state,
+            &br_port_confs,
+            routes,
+            rules,
+            nm_dns_conf.as_ref(),
+            checkpoint,
+        )?);
+    }
+    if let Some(nm_dns_conf) = nm_dns_conf.as_ref() {
+        purge_dns_from_other_conns(&nm_api, nm_dns_conf, &applied_uuids)?;
+    }
+    if let Some(hostname) = chg_net_state.hostname.config.as_ref() {
+        nm_api
+            .hostname_set(hostname)
+            .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
+    }
     Ok(())
 }
 
@@ -39,11 +114,18 @@ fn iface_type_to_nm(
     iface_type: &InterfaceType,
 ) -> Result<String, NmstateError> {
     match iface_type {
+        InterfaceType::Bond => Ok("bond".into()),
+        InterfaceType::Dummy => Ok("dummy".into()),
         InterfaceType::LinuxBridge => Ok("bridge".into()),
         InterfaceType::Ethernet => Ok("802-3-ethernet".into()),
-        // TODO: top level code should change the interface type to
-        // ethernet if no veth peer defined.
-        InterfaceType::Veth => Ok("802-3-ethernet".into()),
+        InterfaceType::OvsBridge => Ok("ovs-bridge".into()),
+        InterfaceType::OvsInterface => Ok("ovs-interface".into()),
+        // NM use macvlan setting with `tap: true` for MacVtap
+        InterfaceType::MacVlan | InterfaceType::MacVtap => Ok("macvlan".into()),
+        InterfaceType::Veth => Ok("veth".into()),
+        InterfaceType::Vlan => Ok("vlan".into()),
+        InterfaceType::Vrf => Ok("vrf".into()),
+        InterfaceType::Vxlan => Ok("vxlan".into()),
         _ => Err(NmstateError::new(
             ErrorKind::Bug,
             format!("BUG: NM does not support iface type: {:?}", iface_type),
@@ -51,10 +133,14 @@ fn iface_type_to_nm(
     }
 }
 
-fn iface_to_nm_connection(
+pub(crate) fn iface_to_nm_connection(
     iface: &Interface,
     exist_nm_conns: &[NmConnection],
     nm_ac_uuids: &[&str],
+    br_port_confs: &HashMap<&str, &LinuxBridgePortConfig>,
+    routes: &[RouteEntry],
+    rules: &[RouteRuleEntry],
+    nm_dns_conf: Option<&NmDnsConf>,
 ) -> Result<(String, NmConnection), NmstateError> {
     let base_iface = iface.base_iface();
     let exist_nm_conn = get_exist_profile(
@@ -83,14 +169,45 @@ fn iface_to_nm_connection(
     };
     if let Some(ctrl_name) = &base_iface.controller {
         if let Some(ctrl_type) = &base_iface.controller_type {
-            nm_conn_set.controller = Some(ctrl_name.to_string());
-            nm_conn_set.controller_type = Some(iface_type_to_nm(ctrl_type)?);
+            if ctrl_type == &InterfaceType::OvsBridge {
+                // OVS bridge port is attached to the ovs-port profile
+                nm_conn_set.controller =
+                    Some(get_ovs_port_name(&base_iface.name));
+                nm_conn_set.controller_type =
+                    Some(NM_SETTING_OVS_PORT_SETTING_NAME.to_string());
+            } else {
+                nm_conn_set.controller = Some(ctrl_name.to_string());
+                nm_conn_set.controller_type =
+                    Some(iface_type_to_nm(ctrl_type)?);
+            }
         }
     }
     let mut nm_conn = NmConnection {
         connection: Some(nm_conn_set),
         ..Default::default()
     };
+    if base_iface.controller_type == Some(InterfaceType::LinuxBridge) {
+        nm_conn.bridge_port = if let Some(port_conf) =
+            br_port_confs.get(base_iface.name.as_str())
+        {
+            Some(linux_bridge_port_conf_to_nm(port_conf))
+        } else {
+            exist_nm_conn.and_then(|c| c.bridge_port.clone())
+        };
+    }
+    if nm_iface_type_has_wired(&base_iface.iface_type) {
+        nm_conn.wired = base_iface_to_nm_wired(base_iface, exist_nm_conn)?;
+    }
+    // OVS bridge only exists in user space
+    if base_iface.iface_type != InterfaceType::OvsBridge {
+        nm_conn.ethtool = match base_iface.ethtool.as_ref() {
+            Some(ethtool_conf) => Some(ethtool_conf_to_nm(
+                ethtool_conf,
+                exist_nm_conn.and_then(|c| c.ethtool.as_ref()),
+            )),
+            None => exist_nm_conn.and_then(|c| c.ethtool.clone()),
+        };
+    }
     if base_iface.can_have_ip() {
         if let Some(iface_ip) = &base_iface.ipv4 {
             nm_conn.ipv4 = Some(iface_ipv4_to_nm(&iface_ip)?);
@@ -108,27 +225,247 @@ fn iface_to_nm_connection(
                 ..Default::default()
             })?);
         }
-    }
-    if let Interface::LinuxBridge(br_iface) = iface {
-        if let Some(br_conf) = &br_iface.bridge {
-            nm_conn.bridge = Some(linux_bridge_conf_to_nm(br_conf)?);
+        for (nm_ip_set, is_ipv6) in [
+            (nm_conn.ipv4.as_mut(), false),
+            (nm_conn.ipv6.as_mut(), true),
+        ] {
+            if let Some(nm_ip_set) = nm_ip_set {
+                if nm_ip_set.method != Some(NmSettingIpMethod::Disabled) {
+                    nm_ip_set.routes =
+                        gen_nm_ip_routes(routes, &base_iface.name, is_ipv6)?;
+                    nm_ip_set.route_rules = gen_nm_ip_rules(
+                        rules,
+                        routes,
+                        &base_iface.name,
+                        is_ipv6,
+                    )?;
+                }
+            }
+        }
+        if let Some(nm_dns_conf) = nm_dns_conf {
+            dns_conf_to_nm(&mut nm_conn, &base_iface.name, nm_dns_conf);
+        } else if let Some(exist_nm_conn) = exist_nm_conn {
+            copy_nm_dns(&mut nm_conn, exist_nm_conn);
         }
     }
+    iface_to_nm_conn_specific(iface, &mut nm_conn, exist_nm_conn)?;
     Ok((uuid, nm_conn))
 }
 
+// Type specific NM settings, the NM connection holds the settings of base
+// interface already.
+fn iface_to_nm_conn_specific(
+    iface: &Interface,
+    nm_conn: &mut NmConnection,
+    exist_nm_conn: Option<&NmConnection>,
+) -> Result<(), NmstateError> {
+    match iface {
+        Interface::Ethernet(eth_iface) => {
+            if let Some(eth_conf) = &eth_iface.ethernet {
+                let mut nm_wired = nm_conn.wired.take().unwrap_or_default();
+                eth_conf_to_nm(iface.name(), eth_conf, &mut nm_wired)?;
+                nm_conn.wired = Some(nm_wired);
+            }
+        }
+        Interface::LinuxBridge(br_iface) => {
+            if let Some(br_conf) = &br_iface.bridge {
+                nm_conn.bridge = Some(linux_bridge_conf_to_nm(br_conf)?);
+            }
+        }
+        Interface::Bond(bond_iface) => {
+            if let Some(bond_conf) = &bond_iface.link_aggregation {
+                nm_conn.bond = Some(bond_conf_to_nm(bond_conf)?);
+            }
+        }
+        Interface::Veth(veth_iface) => {
+            if let Some(veth_conf) = &veth_iface.veth {
+                nm_conn.veth = Some(NmSettingVeth {
+                    peer: Some(veth_conf.peer.clone()),
+                });
+            } else {
+                nm_conn.veth = exist_nm_conn.and_then(|c| c.veth.clone());
+            }
+            // Veth without peer known can only be managed as ethernet
+            if nm_conn.veth.is_none() {
+                if let Some(nm_conn_set) = nm_conn.connection.as_mut() {
+                    nm_conn_set.iface_type =
+                        Some(NM_SETTING_WIRED_SETTING_NAME.to_string());
+                }
+            }
+        }
+        Interface::Vlan(vlan_iface) => {
+            if let Some(vlan_conf) = &vlan_iface.vlan {
+                nm_conn.vlan = Some(vlan_conf_to_nm(vlan_conf)?);
+            }
+        }
+        Interface::Vxlan(vxlan_iface) => {
+            if let Some(vxlan_conf) = &vxlan_iface.vxlan {
+                nm_conn.vxlan = Some(vxlan_conf_to_nm(vxlan_conf));
+            }
+        }
+        Interface::OvsBridge(ovs_br_iface) => {
+            if let Some(ovs_br_conf) = &ovs_br_iface.bridge {
+                nm_conn.ovs_bridge = Some(ovs_bridge_conf_to_nm(ovs_br_conf));
+            }
+        }
+        Interface::OvsInterface(_) => {
+            nm_conn.ovs_iface = Some(ovs_iface_to_nm());
+        }
+        Interface::Vrf(vrf_iface) => {
+            if let Some(vrf_conf) = &vrf_iface.vrf {
+                nm_conn.vrf = Some(vrf_conf_to_nm(vrf_conf));
+            }
+        }
+        Interface::MacVlan(mac_vlan_iface) => {
+            if let Some(conf) = &mac_vlan_iface.mac_vlan {
+                nm_conn.mac_vlan = Some(mac_vlan_conf_to_nm(
+                    &conf.base_iface,
+                    &conf.mode,
+                    conf.promiscuous,
+                    false,
+                ));
+            }
+        }
+        Interface::MacVtap(mac_vtap_iface) => {
+            if let Some(conf) = &mac_vtap_iface.mac_vtap {
+                nm_conn.mac_vlan = Some(mac_vlan_conf_to_nm(
+                    &conf.base_iface,
+                    &conf.mode,
+                    conf.promiscuous,
+                    true,
+                ));
+            }
+        }
+        Interface::Dummy(_) | Interface::Unknown(_) => (),
+    }
+    Ok(())
+}
+
+// NM only force the speed and duplex when auto negotiation is disabled with
+// both of them defined.
+fn eth_conf_to_nm(
+    iface_name: &str,
+    eth_conf: &EthernetConfig,
+    nm_wired: &mut NmSettingWired,
+) -> Result<(), NmstateError> {
+    if eth_conf.auto_negotiation == Some(false)
+        && (eth_conf.speed.is_none() || eth_conf.duplex.is_none())
+    {
+        return Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Both speed and duplex are required for interface {} when \
+                auto-negotiation is disabled",
+                iface_name
+            ),
+        ));
+    }
+    if eth_conf.auto_negotiation.is_some() {
+        nm_wired.auto_negotiate = eth_conf.auto_negotiation;
+    }
+    if eth_conf.speed.is_some() {
+        nm_wired.speed = eth_conf.speed;
+    }
+    if let Some(duplex) = eth_conf.duplex.as_ref() {
+        nm_wired.duplex = Some(duplex.to_string());
+    }
+    Ok(())
+}
+
+// Interface types accepting the NM 802-3-ethernet setting
+// NetworkManager has no wired setting to hold MTU and MAC address of other
+// interface types. Values identical to current ones are still allowed, so
+// that the output of `show` can be applied back.
+fn check_link_conf_without_wired(
+    iface: &Interface,
+    cur_iface: Option<&Interface>,
+) -> Result<(), NmstateError> {
+    let base_iface = iface.base_iface();
+    if nm_iface_type_has_wired(&base_iface.iface_type) {
+        return Ok(());
+    }
+    let cur_base_iface = cur_iface.map(|i| i.base_iface());
+    let mtu_changed = base_iface.mtu.is_some()
+        && base_iface.mtu != cur_base_iface.and_then(|i| i.mtu);
+    let mac_changed = match base_iface.mac_address.as_deref() {
+        Some(mac) => !cur_base_iface
+            .and_then(|i| i.mac_address.as_deref())
+            .map(|cur_mac| cur_mac.eq_ignore_ascii_case(mac))
+            .unwrap_or_default(),
+        None => false,
+    };
+    if mtu_changed || mac_changed {
+        return Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "MTU and MAC address of {} interface {} are not supported \
+                by NetworkManager plugin",
+                base_iface.iface_type, base_iface.name
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn nm_iface_type_has_wired(iface_type: &InterfaceType) -> bool {
+    matches!(
+        iface_type,
+        InterfaceType::Ethernet
+            | InterfaceType::Veth
+            | InterfaceType::Bond
+            | InterfaceType::LinuxBridge
+            | InterfaceType::Vlan
+            | InterfaceType::Vxlan
+            | InterfaceType::MacVlan
+            | InterfaceType::MacVtap
+            | InterfaceType::Dummy
+            | InterfaceType::OvsInterface
+    )
+}
+
+// Preserve the MTU and MAC address of existing profile when not desired
+fn base_iface_to_nm_wired(
+    base_iface: &BaseInterface,
+    exist_nm_conn: Option<&NmConnection>,
+) -> Result<Option<NmSettingWired>, NmstateError> {
+    let mut nm_wired = exist_nm_conn
+        .and_then(|c| c.wired.clone())
+        .unwrap_or_default();
+    if let Some(mac) = &base_iface.mac_address {
+        nm_wired.cloned_mac_address = Some(mac.to_string());
+    }
+    if let Some(mtu) = base_iface.mtu {
+        nm_wired.mtu = Some(u32::try_from(mtu).or_else(|_| {
+            Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "MTU {} of interface {} is larger than {}",
+                    mtu,
+                    base_iface.name,
+                    u32::MAX
+                ),
+            ))
+        })?);
+    }
+    Ok(if nm_wired == NmSettingWired::default() {
+        None
+    } else {
+        Some(nm_wired)
+    })
+}
+
 fn iface_ipv4_to_nm(
     iface_ip: &InterfaceIpv4,
 ) -> Result<NmSettingIp, NmstateError> {
-    let mut addresses: Vec<String> = Vec::new();
+    let addresses = if iface_ip.enabled {
+        gen_nm_ip_addrs(&iface_ip.addresses)
+    } else {
+        Vec::new()
+    };
     let method = if iface_ip.enabled {
         if iface_ip.dhcp {
             NmSettingIpMethod::Auto
-        } else if iface_ip.addresses.len() > 0 {
-            for ip_addr in &iface_ip.addresses {
-                addresses
-                    .push(format!("{}/{}", ip_addr.ip, ip_addr.prefix_length));
-            }
+        } else if addresses.len() > 0 {
             NmSettingIpMethod::Manual
         } else {
             NmSettingIpMethod::Disabled
@@ -136,35 +473,49 @@ fn iface_ipv4_to_nm(
     } else {
         NmSettingIpMethod::Disabled
     };
-    Ok(NmSettingIp {
+    let mut nm_setting = NmSettingIp {
         method: Some(method),
         addresses,
         ..Default::default()
-    })
+    };
+    if iface_ip.enabled && iface_ip.dhcp {
+        nm_setting.ignore_auto_dns = iface_ip.auto_dns.map(|v| !v);
+        nm_setting.never_default = iface_ip.auto_gateway.map(|v| !v);
+        nm_setting.ignore_auto_routes = iface_ip.auto_routes.map(|v| !v);
+        nm_setting.route_table = iface_ip.auto_table_id;
+        nm_setting.route_metric = iface_ip.auto_route_metric.map(i64::from);
+        nm_setting.dhcp_client_id = iface_ip.dhcp_client_id.clone();
+        nm_setting.dhcp_send_hostname = iface_ip.dhcp_send_hostname;
+        nm_setting.dhcp_hostname = iface_ip.dhcp_custom_hostname.clone();
+        nm_setting.dhcp_timeout = iface_ip.dhcp_timeout;
+    }
+    Ok(nm_setting)
 }
 
 fn iface_ipv6_to_nm(
     iface_ip: &InterfaceIpv6,
 ) -> Result<NmSettingIp, NmstateError> {
-    let mut addresses: Vec<String> = Vec::new();
+    let addresses = if iface_ip.enabled {
+        gen_nm_ip_addrs(&iface_ip.addresses)
+    } else {
+        Vec::new()
+    };
     let method = if iface_ip.enabled {
         match (iface_ip.dhcp, iface_ip.autoconf) {
             (true, true) => NmSettingIpMethod::Auto,
             (true, false) => NmSettingIpMethod::Dhcp,
+            // NM `auto` method starts DHCPv6 whenever router advertisement
+            // requests so, it cannot guarantee DHCPv6 stays off.
             (false, true) => {
                 return Err(NmstateError::new(
                     ErrorKind::NotImplementedError,
-                    "Autoconf without DHCP is not supported yet".to_string(),
+                    "IPv6 autoconf without DHCP is not supported by \
+                    NetworkManager plugin, please use kernel only mode"
+                        .to_string(),
                 ))
             }
             (false, false) => {
-                if iface_ip.addresses.len() > 0 {
-                    for ip_addr in &iface_ip.addresses {
-                        addresses.push(format!(
-                            "{}/{}",
-                            ip_addr.ip, ip_addr.prefix_length
-                        ));
-                    }
+                if addresses.len() > 0 {
                     NmSettingIpMethod::Manual
                 } else {
                     NmSettingIpMethod::Disabled
@@ -174,31 +525,184 @@ fn iface_ipv6_to_nm(
     } else {
         NmSettingIpMethod::Disabled
     };
-    Ok(NmSettingIp {
+    let mut nm_setting = NmSettingIp {
         method: Some(method),
         addresses,
         ..Default::default()
-    })
+    };
+    if iface_ip.enabled && iface_ip.is_auto() {
+        nm_setting.ignore_auto_dns = iface_ip.auto_dns.map(|v| !v);
+        nm_setting.never_default = iface_ip.auto_gateway.map(|v| !v);
+        nm_setting.ignore_auto_routes = iface_ip.auto_routes.map(|v| !v);
+        nm_setting.route_table = iface_ip.auto_table_id;
+        nm_setting.route_metric = iface_ip.auto_route_metric.map(i64::from);
+        nm_setting.dhcp_duid = iface_ip.dhcp_duid.clone();
+        nm_setting.dhcp_send_hostname = iface_ip.dhcp_send_hostname;
+        nm_setting.dhcp_hostname = iface_ip.dhcp_custom_hostname.clone();
+        nm_setting.dhcp_timeout = iface_ip.dhcp_timeout;
+    }
+    if iface_ip.enabled && iface_ip.autoconf {
+        nm_setting.addr_gen_mode =
+            iface_ip.addr_gen_mode.as_ref().map(nms_addr_gen_mode_to_nm);
+        nm_setting.token = iface_ip.token.clone();
+    }
+    Ok(nm_setting)
+}
+
+// Static IP addresses are also supported along with DHCP or IPv6 autoconf,
+// the dynamic IP addresses are ignored.
+fn gen_nm_ip_addrs(ip_addrs: &[InterfaceIpAddr]) -> Vec<String> {
+    ip_addrs
+        .iter()
+        .filter(|ip_addr| !ip_addr.is_auto())
+        .map(|ip_addr| format!("{}/{}", ip_addr.ip, ip_addr.prefix_length))
+        .collect()
 }
 
 fn linux_bridge_conf_to_nm(
     br_conf: &LinuxBridgeConfig,
 ) -> Result<NmSettingBridge, NmstateError> {
-    if let Some(LinuxBridgeOptions {
-        stp:
-            Some(LinuxBridgeStpOptions {
-                enabled: stp_enabled,
-                ..
-            }),
-        ..
-    }) = br_conf.options
-    {
-        return Ok(NmSettingBridge {
-            stp: stp_enabled,
-            ..Default::default()
+    let mut nm_br_set = NmSettingBridge::default();
+    if let Some(opts) = &br_conf.options {
+        if let Some(stp_opts) = &opts.stp {
+            nm_br_set.stp = stp_opts.enabled;
+            nm_br_set.forward_delay = stp_opts.forward_delay.map(u32::from);
+            nm_br_set.hello_time = stp_opts.hello_time.map(u32::from);
+            nm_br_set.max_age = stp_opts.max_age.map(u32::from);
+            nm_br_set.priority = stp_opts.priority.map(u32::from);
+        }
+        nm_br_set.ageing_time = opts.mac_ageing_time;
+        nm_br_set.group_forward_mask = opts.group_forward_mask.map(u32::from);
+        nm_br_set.multicast_hash_max = opts.hash_max;
+        nm_br_set.multicast_last_member_count =
+            opts.multicast_last_member_count;
+        nm_br_set.multicast_last_member_interval =
+            opts.multicast_last_member_interval;
+        nm_br_set.multicast_membership_interval =
+            opts.multicast_membership_interval;
+        nm_br_set.multicast_querier = opts.multicast_querier;
+        nm_br_set.multicast_querier_interval = opts.multicast_querier_interval;
+        nm_br_set.multicast_query_interval = opts.multicast_query_interval;
+        nm_br_set.multicast_query_response_interval =
+            opts.multicast_query_response_interval;
+        nm_br_set.multicast_query_use_ifaddr = opts.multicast_query_use_ifaddr;
+        nm_br_set.multicast_router =
+            opts.multicast_router.map(|r| r.to_string());
+        nm_br_set.multicast_snooping = opts.multicast_snooping;
+        nm_br_set.multicast_startup_query_count =
+            opts.multicast_startup_query_count;
+        nm_br_set.multicast_startup_query_interval =
+            opts.multicast_startup_query_interval;
+        // NM is using upper case Q for 802.1Q
+        nm_br_set.vlan_protocol = opts.vlan_protocol.map(|p| match p {
+            VlanProtocol::Ieee8021Q => "802.1Q".to_string(),
+            VlanProtocol::Ieee8021Ad => "802.1ad".to_string(),
         });
     }
-    Ok(NmSettingBridge::default())
+    nm_br_set.vlan_filtering = br_conf.vlan_filtering();
+    Ok(nm_br_set)
+}
+
+fn linux_bridge_port_conf_to_nm(
+    port_conf: &LinuxBridgePortConfig,
+) -> NmSettingBridgePort {
+    NmSettingBridgePort {
+        hairpin_mode: port_conf.stp_hairpin_mode,
+        path_cost: port_conf.stp_path_cost,
+        priority: port_conf.stp_priority.map(u32::from),
+        vlans: port_conf.vlan.as_ref().map(|vlan_conf| {
+            vlan_conf
+                .to_vlan_ranges()
+                .into_iter()
+                .map(|(min, max, is_pvid, is_untagged)| {
+                    NmSettingBridgeVlanRange {
+                        vid_start: min,
+                        vid_end: max,
+                        pvid: is_pvid,
+                        untagged: is_untagged,
+                    }
+                })
+                .collect()
+        }),
+    }
+}
+
+fn bond_conf_to_nm(
+    bond_conf: &BondConfig,
+) -> Result<NmSettingBond, NmstateError> {
+    let mut options: HashMap<String, String> = HashMap::new();
+    if let Some(mode) = &bond_conf.mode {
+        options.insert("mode".to_string(), mode.to_string());
+    }
+    if let Some(bond_opts) = &bond_conf.options {
+        for (key, value) in bond_opts.to_kernel_options() {
+            options.insert(key.to_string(), value);
+        }
+    }
+    if !options.contains_key("mode") {
+        return Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            "Bond mode is mandatory for NetworkManager".to_string(),
+        ));
+    }
+    Ok(NmSettingBond { options })
+}
+
+fn vlan_conf_to_nm(
+    vlan_conf: &VlanConfig,
+) -> Result<NmSettingVlan, NmstateError> {
+    if vlan_conf.protocol == Some(VlanProtocol::Ieee8021Ad) {
+        return Err(NmstateError::new(
+            ErrorKind::NotImplementedError,
+            format!(
+                "VLAN protocol {} is not supported by NetworkManager \
+                plugin yet",
+                VlanProtocol::Ieee8021Ad
+            ),
+        ));
+    }
+    Ok(NmSettingVlan {
+        parent: Some(vlan_conf.base_iface.to_string()),
+        id: Some(vlan_conf.id.into()),
+    })
+}
+
+fn vxlan_conf_to_nm(vxlan_conf: &VxlanConfig) -> NmSettingVxlan {
+    NmSettingVxlan {
+        parent: vxlan_conf.base_iface.clone(),
+        id: Some(vxlan_conf.id),
+        remote: vxlan_conf.remote.clone(),
+        local: vxlan_conf.local.clone(),
+        dst_port: vxlan_conf.destination_port.map(u32::from),
+        learning: vxlan_conf.learning,
+    }
+}
+
+fn vrf_conf_to_nm(vrf_conf: &VrfConfig) -> NmSettingVrf {
+    NmSettingVrf {
+        table: Some(vrf_conf.route_table_id),
+    }
+}
+
+fn mac_vlan_conf_to_nm(
+    base_iface: &str,
+    mode: &MacVlanMode,
+    promiscuous: Option<bool>,
+    tap: bool,
+) -> NmSettingMacVlan {
+    NmSettingMacVlan {
+        parent: Some(base_iface.to_string()),
+        mode: Some(match mode {
+            MacVlanMode::Vepa => 1,
+            MacVlanMode::Bridge => 2,
+            MacVlanMode::Private => 3,
+            MacVlanMode::Passthru => 4,
+            MacVlanMode::Source => 5,
+            MacVlanMode::Unknown => 0,
+        }),
+        promiscuous,
+        tap: Some(tap),
+    }
 }
 
 fn nm_connection_matches(
@@ -206,7 +710,6 @@ fn nm_connection_matches(
     iface_name: &str,
     iface_type: &InterfaceType,
 ) -> bool {
-    // TODO Need to handle veth/ethernet
     let nm_iface_type = match iface_type_to_nm(iface_type) {
         Ok(i) => i,
         Err(e) => {
@@ -219,7 +722,10 @@ fn nm_connection_matches(
         }
     };
     nm_conn.iface_name() == Some(iface_name)
-        && nm_conn.iface_type() == Some(&nm_iface_type)
+        && (nm_conn.iface_type() == Some(&nm_iface_type)
+            // Veth might be managed by ethernet profile
+            || (iface_type == &InterfaceType::Veth
+                && nm_conn.iface_type() == Some(NM_SETTING_WIRED_SETTING_NAME)))
 }
 
 fn delete_exist_profiles(
@@ -267,9 +773,14 @@ fn get_exist_profile<'a>(
 fn apply_single_state(
     nm_api: &NmApi,
     net_state: &NetworkState,
+    br_port_confs: &HashMap<&str, &LinuxBridgePortConfig>,
+    routes: &[RouteEntry],
+    rules: &[RouteRuleEntry],
+    nm_dns_conf: Option<&NmDnsConf>,
     checkpoint: &str,
-) -> Result<(), NmstateError> {
+) -> Result<Vec<String>, NmstateError> {
     let mut nm_conn_uuids: Vec<String> = Vec::new();
+    let mut deleted_uuids: HashSet<&str> = HashSet::new();
     let mut ports: HashMap<String, (String, InterfaceType)> = HashMap::new();
 
     let exist_nm_conns = nm_api
@@ -281,7 +792,11 @@ fn apply_single_state(
     let nm_ac_uuids: Vec<&str> =
         nm_acs.iter().map(|nm_ac| &nm_ac.uuid as &str).collect();
 
-    let ifaces = net_state.interfaces.to_vec();
+    let mut ifaces = net_state.interfaces.to_vec_ordered()?;
+    // Ports and children are removed before their controller and parent
+    if ifaces.iter().all(|iface| iface.is_absent()) {
+        ifaces.reverse();
+    }
     for iface in &ifaces {
         if let Some(iface_ports) = iface.ports() {
             for port_name in iface_ports {
@@ -306,9 +821,36 @@ fn apply_single_state(
             )?;
         }
         index += 1;
+        for uuid in get_stale_ovs_port_uuids(iface, &exist_nm_conns) {
+            // Port and its OVS bridge might both be removed
+            if deleted_uuids.insert(uuid) {
+                nm_api.connection_delete(uuid).or_else(|ref nm_error| {
+                    Err(nm_error_to_nmstate(nm_error))
+                })?;
+            }
+        }
+        if iface.is_absent() {
+            // NM removes the software interface along with its profile,
+            // the peer of veth is also marked as absent by top level code.
+            delete_exist_profiles(
+                &nm_api,
+                &exist_nm_conns,
+                iface.name(),
+                &iface.iface_type(),
+                "",
+            )?;
+            continue;
+        }
         if iface.iface_type() != InterfaceType::Unknown {
-            let (uuid, nm_conn) =
-                iface_to_nm_connection(iface, &exist_nm_conns, &nm_ac_uuids)?;
+            let (uuid, nm_conn) = iface_to_nm_connection(
+                iface,
+                &exist_nm_conns,
+                &nm_ac_uuids,
+                br_port_confs,
+                routes,
+                rules,
+                nm_dns_conf,
+            )?;
             nm_api
                 .connection_add(&nm_conn)
                 .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
@@ -321,6 +863,16 @@ fn apply_single_state(
             )?;
             nm_conn_uuids.push(uuid);
         }
+        if let Interface::OvsBridge(ovs_br_iface) = iface {
+            for (uuid, nm_conn) in
+                gen_nm_ovs_port_conns(ovs_br_iface, &exist_nm_conns)
+            {
+                nm_api.connection_add(&nm_conn).or_else(|ref nm_error| {
+                    Err(nm_error_to_nmstate(nm_error))
+                })?;
+                nm_conn_uuids.push(uuid);
+            }
+        }
     }
     for nm_conn_uuid in &nm_conn_uuids {
         nm_checkpoint_timeout_extend(
@@ -331,5 +883,74 @@ fn apply_single_state(
             .connection_activate(nm_conn_uuid)
             .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
     }
-    Ok(())
+    Ok(nm_conn_uuids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gen_ipv6(value: serde_json::Value) -> InterfaceIpv6 {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_iface_ipv6_to_nm_autoconf_only() {
+        let e = iface_ipv6_to_nm(&gen_ipv6(serde_json::json!({
+            "enabled": true,
+            "dhcp": false,
+            "autoconf": true,
+        })))
+        .unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::NotImplementedError));
+    }
+
+    #[test]
+    fn test_iface_ipv6_to_nm_dhcp_and_autoconf() {
+        let nm_set = iface_ipv6_to_nm(&gen_ipv6(serde_json::json!({
+            "enabled": true,
+            "dhcp": true,
+            "autoconf": true,
+            "address": [{"ip": "2001:db8::1", "prefix-length": 64}],
+            "addr-gen-mode": "eui64",
+            "token": "::fac1",
+            "auto-dns": false,
+        })))
+        .unwrap();
+        assert_eq!(nm_set.method, Some(NmSettingIpMethod::Auto));
+        assert_eq!(nm_set.addresses, vec!["2001:db8::1/64".to_string()]);
+        assert_eq!(nm_set.addr_gen_mode, Some(0));
+        assert_eq!(nm_set.token.as_deref(), Some("::fac1"));
+        assert_eq!(nm_set.ignore_auto_dns, Some(true));
+    }
+
+    #[test]
+    fn test_nm_vrf_link_conf() {
+        let cur_iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "vrf0",
+            "type": "vrf",
+            "mtu": 65575,
+            "mac-address": "00:23:45:67:89:1A",
+        }))
+        .unwrap();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "vrf0",
+            "type": "vrf",
+            "mtu": 65575,
+            "mac-address": "00:23:45:67:89:1a",
+        }))
+        .unwrap();
+        check_link_conf_without_wired(&iface, Some(&cur_iface)).unwrap();
+
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "vrf0",
+            "type": "vrf",
+            "mtu": 1500,
+        }))
+        .unwrap();
+        let e = check_link_conf_without_wired(&iface, Some(&cur_iface))
+            .unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+        assert!(check_link_conf_without_wired(&iface, None).is_err());
+    }
 }
@@ -0,0 +1,196 @@
+use log::info;
+use nm_dbus::{NmApi, NmConnection, NmSettingIp};
+
+use crate::{
+    nm::error::nm_error_to_nmstate, DnsClientState, Interfaces, NmstateError,
+    RouteEntry,
+};
+
+// The desired DNS config along with the interfaces holding it
+pub(crate) struct NmDnsConf<'a> {
+    conf: &'a DnsClientState,
+    ipv4_iface: Option<&'a str>,
+    ipv6_iface: Option<&'a str>,
+}
+
+impl<'a> NmDnsConf<'a> {
+    pub(crate) fn new(
+        conf: &'a DnsClientState,
+        routes: &'a [RouteEntry],
+        ifaces: &[&'a Interfaces],
+        running_routes: &'a [RouteEntry],
+    ) -> Result<Self, NmstateError> {
+        let (ipv4_iface, ipv6_iface) =
+            conf.get_holder_ifaces(routes, ifaces, running_routes)?;
+        Ok(Self {
+            conf,
+            ipv4_iface,
+            ipv6_iface,
+        })
+    }
+}
+
+pub(crate) fn nm_dns_get(nm_conns: &[NmConnection]) -> DnsClientState {
+    let mut servers = Vec::new();
+    let mut searches = Vec::new();
+    let mut options = Vec::new();
+    // NM places IPv4 name servers before IPv6 ones by default
+    let nm_ip_sets = nm_conns
+        .iter()
+        .filter_map(|nm_conn| nm_conn.ipv4.as_ref())
+        .chain(nm_conns.iter().filter_map(|nm_conn| nm_conn.ipv6.as_ref()));
+    for nm_ip_set in nm_ip_sets {
+        for srv in nm_ip_set.dns.as_deref().unwrap_or(&[]) {
+            if !servers.contains(srv) {
+                servers.push(srv.to_string());
+            }
+        }
+        for search in nm_ip_set.dns_search.as_deref().unwrap_or(&[]) {
+            if !searches.contains(search) {
+                searches.push(search.to_string());
+            }
+        }
+        for opt in nm_ip_set.dns_options.as_deref().unwrap_or(&[]) {
+            if !options.contains(opt) {
+                options.push(opt.to_string());
+            }
+        }
+    }
+    DnsClientState {
+        server: Some(servers),
+        search: Some(searches),
+        options: Some(options),
+    }
+}
+
+// Store DNS config into the IP settings of holder interface, the DNS config
+// of other interfaces is purged.
+pub(crate) fn dns_conf_to_nm(
+    nm_conn: &mut NmConnection,
+    iface_name: &str,
+    nm_dns_conf: &NmDnsConf,
+) {
+    let dns_conf = nm_dns_conf.conf;
+    let (ipv4_iface, ipv6_iface) =
+        (nm_dns_conf.ipv4_iface, nm_dns_conf.ipv6_iface);
+    if ipv4_iface == Some(iface_name) {
+        if let Some(nm_ip_set) = nm_conn.ipv4.as_mut() {
+            set_nm_dns(nm_ip_set, dns_conf.get_ipv4_servers(), dns_conf);
+        }
+    }
+    if ipv6_iface == Some(iface_name) {
+        if let Some(nm_ip_set) = nm_conn.ipv6.as_mut() {
+            set_nm_dns(nm_ip_set, dns_conf.get_ipv6_servers(), dns_conf);
+            // DNS search and options are stored in IPv4 holder if exists
+            if ipv4_iface.is_some() {
+                nm_ip_set.dns_search = None;
+                nm_ip_set.dns_options = None;
+            }
+        }
+    }
+}
+
+fn set_nm_dns(
+    nm_ip_set: &mut NmSettingIp,
+    servers: Vec<String>,
+    dns_conf: &DnsClientState,
+) {
+    nm_ip_set.dns = Some(servers);
+    nm_ip_set.dns_search = dns_conf.search.clone();
+    nm_ip_set.dns_options = dns_conf.options.clone();
+    // Prevent DHCP or IPv6 autoconf overriding the desired DNS config unless
+    // auto-dns is explicitly desired.
+    if nm_ip_set.ignore_auto_dns.is_none() {
+        nm_ip_set.ignore_auto_dns = Some(true);
+    }
+}
+
+// Preserve the DNS config of existing profile when DNS is not desired
+pub(crate) fn copy_nm_dns(
+    nm_conn: &mut NmConnection,
+    exist_nm_conn: &NmConnection,
+) {
+    for (nm_ip_set, exist_nm_ip_set) in [
+        (nm_conn.ipv4.as_mut(), exist_nm_conn.ipv4.as_ref()),
+        (nm_conn.ipv6.as_mut(), exist_nm_conn.ipv6.as_ref()),
+    ] {
+        if let (Some(nm_ip_set), Some(exist_nm_ip_set)) =
+            (nm_ip_set, exist_nm_ip_set)
+        {
+            nm_ip_set.dns = exist_nm_ip_set.dns.clone();
+            nm_ip_set.dns_search = exist_nm_ip_set.dns_search.clone();
+            nm_ip_set.dns_options = exist_nm_ip_set.dns_options.clone();
+            nm_ip_set.dns_priority = exist_nm_ip_set.dns_priority;
+            if nm_ip_set.ignore_auto_dns.is_none() {
+                nm_ip_set.ignore_auto_dns = exist_nm_ip_set.ignore_auto_dns;
+            }
+        }
+    }
+}
+
+// Remove DNS config from active profiles which are not the holder of the
+// desired DNS config. Profiles applied by nmstate are skipped as they are
+// already purged. To avoid interrupting unrelated interfaces, the changed
+// profiles are updated and reapplied instead of reactivated.
+pub(crate) fn purge_dns_from_other_conns(
+    nm_api: &NmApi,
+    nm_dns_conf: &NmDnsConf,
+    applied_uuids: &[String],
+) -> Result<(), NmstateError> {
+    let (ipv4_iface, ipv6_iface) =
+        (nm_dns_conf.ipv4_iface, nm_dns_conf.ipv6_iface);
+    let nm_acs = nm_api
+        .nm_active_connections_get()
+        .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
+    let nm_ac_uuids: Vec<&str> =
+        nm_acs.iter().map(|nm_ac| &nm_ac.uuid as &str).collect();
+    let nm_conns = nm_api
+        .nm_connections_get()
+        .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
+
+    for nm_conn in nm_conns {
+        let (uuid, iface_name) = match (nm_conn.uuid(), nm_conn.iface_name()) {
+            (Some(u), Some(i)) => (u.to_string(), i.to_string()),
+            _ => continue,
+        };
+        if !nm_ac_uuids.contains(&uuid.as_str())
+            || applied_uuids.contains(&uuid)
+        {
+            continue;
+        }
+        let mut new_nm_conn = nm_conn.clone();
+        let mut changed = false;
+        if ipv4_iface != Some(iface_name.as_str()) {
+            changed |= purge_nm_dns(new_nm_conn.ipv4.as_mut());
+        }
+        if ipv6_iface != Some(iface_name.as_str()) {
+            changed |= purge_nm_dns(new_nm_conn.ipv6.as_mut());
+        }
+        if changed {
+            info!("Removing DNS config from interface {}", iface_name);
+            nm_api
+                .connection_add(&new_nm_conn)
+                .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
+            nm_api
+                .connection_reapply(&uuid)
+                .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
+        }
+    }
+    Ok(())
+}
+
+fn purge_nm_dns(nm_ip_set: Option<&mut NmSettingIp>) -> bool {
+    if let Some(nm_ip_set) = nm_ip_set {
+        if !nm_ip_set.dns.as_deref().unwrap_or(&[]).is_empty()
+            || !nm_ip_set.dns_search.as_deref().unwrap_or(&[]).is_empty()
+            || !nm_ip_set.dns_options.as_deref().unwrap_or(&[]).is_empty()
+        {
+            nm_ip_set.dns = None;
+            nm_ip_set.dns_search = None;
+            nm_ip_set.dns_options = None;
+            nm_ip_set.ignore_auto_dns = None;
+            return true;
+        }
+    }
+    false
+}
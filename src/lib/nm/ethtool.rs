@@ -0,0 +1,132 @@
+use nm_dbus::NmSettingEthtool;
+
+use crate::{
+    ethtool::{get_feature_alias, get_kernel_feature_name},
+    EthtoolConfig,
+};
+
+const NM_ETHTOOL_FEATURE_PREFIX: &str = "feature-";
+
+// The nmstate coalesce property names and their NM option names
+const NM_ETHTOOL_COALESCE_NAMES: [(&str, &str); 22] = [
+    ("adaptive-rx", "coalesce-adaptive-rx"),
+    ("adaptive-tx", "coalesce-adaptive-tx"),
+    ("pkt-rate-high", "coalesce-pkt-rate-high"),
+    ("pkt-rate-low", "coalesce-pkt-rate-low"),
+    ("rate-sample-interval", "coalesce-sample-interval"),
+    ("rx-frames", "coalesce-rx-frames"),
+    ("rx-frames-high", "coalesce-rx-frames-high"),
+    ("rx-frames-irq", "coalesce-rx-frames-irq"),
+    ("rx-frames-low", "coalesce-rx-frames-low"),
+    ("rx-usecs", "coalesce-rx-usecs"),
+    ("rx-usecs-high", "coalesce-rx-usecs-high"),
+    ("rx-usecs-irq", "coalesce-rx-usecs-irq"),
+    ("rx-usecs-low", "coalesce-rx-usecs-low"),
+    ("stats-block-usecs", "coalesce-stats-block-usecs"),
+    ("tx-frames", "coalesce-tx-frames"),
+    ("tx-frames-high", "coalesce-tx-frames-high"),
+    ("tx-frames-irq", "coalesce-tx-frames-irq"),
+    ("tx-frames-low", "coalesce-tx-frames-low"),
+    ("tx-usecs", "coalesce-tx-usecs"),
+    ("tx-usecs-high", "coalesce-tx-usecs-high"),
+    ("tx-usecs-irq", "coalesce-tx-usecs-irq"),
+    ("tx-usecs-low", "coalesce-tx-usecs-low"),
+];
+
+// Ethtool settings not desired are preserved from existing profile
+pub(crate) fn ethtool_conf_to_nm(
+    ethtool_conf: &EthtoolConfig,
+    exist_nm_ethtool: Option<&NmSettingEthtool>,
+) -> NmSettingEthtool {
+    let mut nm_ethtool = exist_nm_ethtool.cloned().unwrap_or_default();
+    if let Some(pause) = ethtool_conf.pause.as_ref() {
+        if pause.autoneg.is_some() {
+            nm_ethtool.pause_autoneg = pause.autoneg;
+        }
+        if pause.rx.is_some() {
+            nm_ethtool.pause_rx = pause.rx;
+        }
+        if pause.tx.is_some() {
+            nm_ethtool.pause_tx = pause.tx;
+        }
+    }
+    if let Some(ring) = ethtool_conf.ring.as_ref() {
+        if ring.rx.is_some() {
+            nm_ethtool.ring_rx = ring.rx;
+        }
+        if ring.rx_jumbo.is_some() {
+            nm_ethtool.ring_rx_jumbo = ring.rx_jumbo;
+        }
+        if ring.rx_mini.is_some() {
+            nm_ethtool.ring_rx_mini = ring.rx_mini;
+        }
+        if ring.tx.is_some() {
+            nm_ethtool.ring_tx = ring.tx;
+        }
+    }
+    if let Some(coalesce) = ethtool_conf.coalesce.as_ref() {
+        let props = coalesce.to_props();
+        for (nms_name, nm_name) in NM_ETHTOOL_COALESCE_NAMES.iter() {
+            if let Some(v) = props.get(*nms_name) {
+                nm_ethtool.coalesce.insert(nm_name.to_string(), *v);
+            }
+        }
+    }
+    if let Some(features) = ethtool_conf.feature.as_ref() {
+        for (name, enabled) in features.iter() {
+            nm_ethtool.features.insert(
+                format!(
+                    "{}{}",
+                    NM_ETHTOOL_FEATURE_PREFIX,
+                    nms_feature_name_to_nm(name)
+                ),
+                *enabled,
+            );
+        }
+    }
+    nm_ethtool
+}
+
+// NM prefers the ethtool command line aliases over kernel feature names
+fn nms_feature_name_to_nm(name: &str) -> &str {
+    let kernel_name = get_kernel_feature_name(name);
+    get_feature_alias(kernel_name).unwrap_or(kernel_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EthtoolCoalesceConfig;
+
+    #[test]
+    fn test_coalesce_names_cover_all_props() {
+        let mut props = serde_json::Map::new();
+        for (nms_name, _) in NM_ETHTOOL_COALESCE_NAMES.iter() {
+            props.insert(
+                nms_name.to_string(),
+                if nms_name.starts_with("adaptive-") {
+                    serde_json::Value::Bool(true)
+                } else {
+                    serde_json::Value::from(10u32)
+                },
+            );
+        }
+        let coalesce: EthtoolCoalesceConfig =
+            serde_json::from_value(serde_json::Value::Object(props)).unwrap();
+        assert_eq!(coalesce.to_props().len(), NM_ETHTOOL_COALESCE_NAMES.len());
+
+        let nm_ethtool = ethtool_conf_to_nm(
+            &EthtoolConfig {
+                coalesce: Some(coalesce),
+                ..Default::default()
+            },
+            None,
+        );
+        assert_eq!(
+            nm_ethtool.coalesce.get("coalesce-sample-interval"),
+            Some(&10)
+        );
+        assert_eq!(nm_ethtool.coalesce.get("coalesce-adaptive-rx"), Some(&1));
+        assert_eq!(nm_ethtool.coalesce.len(), NM_ETHTOOL_COALESCE_NAMES.len());
+    }
+}
@@ -0,0 +1,98 @@
+use nm_dbus::{NmApi, NmConnection};
+
+use crate::{
+    nm::apply::iface_to_nm_connection,
+    nm::dns::NmDnsConf,
+    nm::error::nm_error_to_nmstate,
+    nm::ovs::{
+        gen_nm_ovs_port_conns, NM_SETTING_OVS_BRIDGE_SETTING_NAME,
+        NM_SETTING_OVS_IFACE_SETTING_NAME,
+    },
+    ErrorKind, Interface, InterfaceType, NetworkState, NmstateError,
+};
+
+const NM_KEYFILE_EXTENSION: &str = "nmconnection";
+
+// Generate NetworkManager keyfiles without D-Bus connection, the current
+// state is treated as empty, hence every interface is in add_net_state.
+// Return a list of (file_name, file_content).
+pub(crate) fn nm_gen_conf(
+    add_net_state: &NetworkState,
+    chg_net_state: &NetworkState,
+) -> Result<Vec<(String, String)>, NmstateError> {
+    let br_port_confs = add_net_state.interfaces.get_br_port_confs();
+    // The full route and route rule config are stored in chg_net_state
+    let routes = chg_net_state.routes.config.as_deref().unwrap_or(&[]);
+    let rules = chg_net_state.route_rules.config.as_deref().unwrap_or(&[]);
+    let nm_dns_conf = match chg_net_state.dns.config.as_ref() {
+        Some(dns_conf) => Some(NmDnsConf::new(
+            dns_conf,
+            routes,
+            &[&add_net_state.interfaces, &chg_net_state.interfaces],
+            &[],
+        )?),
+        None => None,
+    };
+
+    let mut ret = Vec::new();
+    for iface in add_net_state.interfaces.to_vec_ordered()? {
+        if iface.iface_type() == InterfaceType::Unknown {
+            return Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Interface {} has no type defined, which is required \
+                    when generating configuration offline",
+                    iface.name()
+                ),
+            ));
+        }
+        let (_, mut nm_conn) = iface_to_nm_connection(
+            iface,
+            &[],
+            &[],
+            &br_port_confs,
+            routes,
+            rules,
+            nm_dns_conf.as_ref(),
+        )?;
+        ret.push(nm_conn_to_keyfile(&mut nm_conn)?);
+        if let Interface::OvsBridge(ovs_br_iface) = iface {
+            for (_, mut nm_conn) in gen_nm_ovs_port_conns(ovs_br_iface, &[]) {
+                ret.push(nm_conn_to_keyfile(&mut nm_conn)?);
+            }
+        }
+    }
+    Ok(ret)
+}
+
+// Random UUID would make the generated keyfiles different on every run
+fn nm_conn_to_keyfile(
+    nm_conn: &mut NmConnection,
+) -> Result<(String, String), NmstateError> {
+    let uuid = NmApi::uuid_gen_by_iface(
+        nm_conn.iface_name().unwrap_or_default(),
+        nm_conn.iface_type().unwrap_or_default(),
+    );
+    if let Some(nm_conn_set) = nm_conn.connection.as_mut() {
+        nm_conn_set.uuid = Some(uuid);
+    }
+    let content = nm_conn
+        .to_keyfile()
+        .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
+    Ok((gen_keyfile_name(nm_conn), content))
+}
+
+// OVS bridge and OVS internal interface are allowed to share the same name,
+// use the NM connection type as suffix to avoid file name conflict.
+fn gen_keyfile_name(nm_conn: &NmConnection) -> String {
+    let name = nm_conn.iface_name().unwrap_or_default();
+    match nm_conn.iface_type() {
+        Some(iface_type)
+            if iface_type == NM_SETTING_OVS_BRIDGE_SETTING_NAME
+                || iface_type == NM_SETTING_OVS_IFACE_SETTING_NAME =>
+        {
+            format!("{}-{}.{}", name, iface_type, NM_KEYFILE_EXTENSION)
+        }
+        _ => format!("{}.{}", name, NM_KEYFILE_EXTENSION),
+    }
+}
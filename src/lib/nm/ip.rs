@@ -0,0 +1,29 @@
+use log::warn;
+
+use crate::Ipv6AddrGenMode;
+
+const NM_IPV6_ADDR_GEN_MODE_EUI64: i32 = 0;
+const NM_IPV6_ADDR_GEN_MODE_STABLE_PRIVACY: i32 = 1;
+
+pub(crate) fn nms_addr_gen_mode_to_nm(addr_gen_mode: &Ipv6AddrGenMode) -> i32 {
+    match addr_gen_mode {
+        Ipv6AddrGenMode::Eui64 => NM_IPV6_ADDR_GEN_MODE_EUI64,
+        Ipv6AddrGenMode::StablePrivacy => NM_IPV6_ADDR_GEN_MODE_STABLE_PRIVACY,
+    }
+}
+
+// NM uses stable-privacy when addr-gen-mode is not defined
+pub(crate) fn nm_addr_gen_mode_to_nms(
+    nm_addr_gen_mode: Option<i32>,
+) -> Option<Ipv6AddrGenMode> {
+    match nm_addr_gen_mode.unwrap_or(NM_IPV6_ADDR_GEN_MODE_STABLE_PRIVACY) {
+        NM_IPV6_ADDR_GEN_MODE_EUI64 => Some(Ipv6AddrGenMode::Eui64),
+        NM_IPV6_ADDR_GEN_MODE_STABLE_PRIVACY => {
+            Some(Ipv6AddrGenMode::StablePrivacy)
+        }
+        mode => {
+            warn!("Unsupported NM IPv6 addr-gen-mode {}", mode);
+            None
+        }
+    }
+}
@@ -1,9 +1,17 @@
 mod apply;
 mod checkpoint;
+mod dns;
 mod error;
+mod ethtool;
+mod gen_conf;
+mod ip;
+mod ovs;
+mod route;
+mod route_rule;
 mod show;
 
 pub(crate) use show::*;
 //pub(crate) use error::*;
 pub(crate) use apply::*;
 pub(crate) use checkpoint::*;
+pub(crate) use gen_conf::nm_gen_conf;
@@ -0,0 +1,301 @@
+use log::warn;
+use nm_dbus::{
+    NmApi, NmConnection, NmSettingConnection, NmSettingOvsBridge,
+    NmSettingOvsIface,
+};
+
+use crate::{
+    Interface, NmstateInterface, OvsBridgeConfig, OvsBridgeFailMode,
+    OvsBridgeInterface, OvsBridgeOptions, OvsBridgePortConfig,
+};
+
+pub(crate) const NM_SETTING_OVS_BRIDGE_SETTING_NAME: &str = "ovs-bridge";
+pub(crate) const NM_SETTING_OVS_PORT_SETTING_NAME: &str = "ovs-port";
+pub(crate) const NM_SETTING_OVS_IFACE_SETTING_NAME: &str = "ovs-interface";
+
+const OVS_PORT_NAME_PREFIX: &str = "ovs-port-";
+const OVS_IFACE_TYPE_INTERNAL: &str = "internal";
+
+// NetworkManager requires an ovs-port profile sitting between the OVS bridge
+// and each of its port interfaces. Nmstate does not expose it, but names it
+// after the port interface so it could be found again.
+pub(crate) fn get_ovs_port_name(iface_name: &str) -> String {
+    format!("{}{}", OVS_PORT_NAME_PREFIX, iface_name)
+}
+
+pub(crate) fn ovs_bridge_conf_to_nm(
+    br_conf: &OvsBridgeConfig,
+) -> NmSettingOvsBridge {
+    if let Some(opts) = &br_conf.options {
+        NmSettingOvsBridge {
+            stp: opts.stp,
+            rstp: opts.rstp,
+            mcast_snooping_enable: opts.mcast_snooping_enable,
+            fail_mode: opts.fail_mode.map(|m| m.to_string()),
+        }
+    } else {
+        NmSettingOvsBridge::default()
+    }
+}
+
+pub(crate) fn ovs_iface_to_nm() -> NmSettingOvsIface {
+    NmSettingOvsIface {
+        iface_type: Some(OVS_IFACE_TYPE_INTERNAL.to_string()),
+    }
+}
+
+pub(crate) fn gen_nm_ovs_port_conns(
+    ovs_br_iface: &OvsBridgeInterface,
+    exist_nm_conns: &[NmConnection],
+) -> Vec<(String, NmConnection)> {
+    let mut ret = Vec::new();
+    for port_name in ovs_br_iface.ports().unwrap_or_default() {
+        let ovs_port_name = get_ovs_port_name(port_name);
+        let uuid = exist_nm_conns
+            .iter()
+            .find(|nm_conn| {
+                nm_conn.iface_type() == Some(NM_SETTING_OVS_PORT_SETTING_NAME)
+                    && nm_conn.iface_name() == Some(ovs_port_name.as_str())
+            })
+            .and_then(|nm_conn| nm_conn.uuid())
+            .map(|uuid| uuid.to_string())
+            .unwrap_or_else(NmApi::uuid_gen);
+        let nm_conn = NmConnection {
+            connection: Some(NmSettingConnection {
+                id: Some(ovs_port_name.clone()),
+                uuid: Some(uuid.clone()),
+                iface_type: Some(NM_SETTING_OVS_PORT_SETTING_NAME.to_string()),
+                iface_name: Some(ovs_port_name),
+                controller: Some(ovs_br_iface.base.name.clone()),
+                controller_type: Some(
+                    NM_SETTING_OVS_BRIDGE_SETTING_NAME.to_string(),
+                ),
+                autoconnect_ports: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        ret.push((uuid, nm_conn));
+    }
+    ret
+}
+
+// The ovs-port profiles left behind when port is detached from OVS bridge or
+// when the OVS bridge or port interface is removed.
+pub(crate) fn get_stale_ovs_port_uuids<'a>(
+    iface: &Interface,
+    exist_nm_conns: &'a [NmConnection],
+) -> Vec<&'a str> {
+    let mut ret = Vec::new();
+    for nm_conn in exist_nm_conns.iter().filter(|nm_conn| {
+        nm_conn.iface_type() == Some(NM_SETTING_OVS_PORT_SETTING_NAME)
+    }) {
+        let ovs_port_name = nm_conn.iface_name().unwrap_or_default();
+        let is_stale = match iface {
+            Interface::OvsBridge(ovs_br_iface) => {
+                is_controlled_by_ovs_bridge(
+                    nm_conn,
+                    &ovs_br_iface.base.name,
+                    exist_nm_conns,
+                ) && (iface.is_absent()
+                    || ovs_br_iface.ports().map(|ports| {
+                        !ports
+                            .iter()
+                            .any(|p| get_ovs_port_name(p) == ovs_port_name)
+                    }) == Some(true))
+            }
+            _ => {
+                iface.is_absent()
+                    && get_ovs_port_name(iface.name()) == ovs_port_name
+            }
+        };
+        if is_stale {
+            if let Some(uuid) = nm_conn.uuid() {
+                ret.push(uuid);
+            }
+        }
+    }
+    ret
+}
+
+fn is_controlled_by_ovs_bridge(
+    nm_conn: &NmConnection,
+    br_name: &str,
+    exist_nm_conns: &[NmConnection],
+) -> bool {
+    nm_conn.controller() == Some(br_name)
+        || exist_nm_conns.iter().any(|nm_br_conn| {
+            nm_br_conn.iface_type() == Some(NM_SETTING_OVS_BRIDGE_SETTING_NAME)
+                && nm_br_conn.iface_name() == Some(br_name)
+                && is_controlled_by(nm_conn, nm_br_conn)
+        })
+}
+
+fn nm_ovs_fail_mode_to_nmstate(fail_mode: &str) -> Option<OvsBridgeFailMode> {
+    match fail_mode {
+        "secure" => Some(OvsBridgeFailMode::Secure),
+        "standalone" => Some(OvsBridgeFailMode::Standalone),
+        "" => None,
+        _ => {
+            warn!("Unsupported OVS bridge fail mode {}", fail_mode);
+            None
+        }
+    }
+}
+
+// The port list of OVS bridge is resolved by walking through
+// interface -> ovs-port -> ovs-bridge profiles.
+pub(crate) fn nm_ovs_bridge_conf_get(
+    nm_br_conn: &NmConnection,
+    nm_conns: &[NmConnection],
+) -> OvsBridgeConfig {
+    let mut ports = Vec::new();
+    for nm_ovs_port_conn in nm_conns.iter().filter(|nm_conn| {
+        nm_conn.iface_type() == Some(NM_SETTING_OVS_PORT_SETTING_NAME)
+            && is_controlled_by(nm_conn, nm_br_conn)
+    }) {
+        for nm_conn in nm_conns {
+            if nm_conn.controller_type()
+                == Some(NM_SETTING_OVS_PORT_SETTING_NAME)
+                && is_controlled_by(nm_conn, nm_ovs_port_conn)
+            {
+                if let Some(iface_name) = nm_conn.iface_name() {
+                    ports.push(OvsBridgePortConfig {
+                        name: iface_name.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    OvsBridgeConfig {
+        options: nm_br_conn.ovs_bridge.as_ref().map(|nm_ovs_br_set| {
+            OvsBridgeOptions {
+                stp: nm_ovs_br_set.stp,
+                rstp: nm_ovs_br_set.rstp,
+                mcast_snooping_enable: nm_ovs_br_set.mcast_snooping_enable,
+                fail_mode: nm_ovs_br_set
+                    .fail_mode
+                    .as_deref()
+                    .and_then(nm_ovs_fail_mode_to_nmstate),
+            }
+        }),
+        port: Some(ports),
+    }
+}
+
+// NM allows referring controller by either interface name or UUID
+fn is_controlled_by(
+    nm_conn: &NmConnection,
+    nm_ctrl_conn: &NmConnection,
+) -> bool {
+    match nm_conn.controller() {
+        Some(ctrl) => {
+            nm_ctrl_conn.iface_name() == Some(ctrl)
+                || nm_ctrl_conn.uuid() == Some(ctrl)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nm_dbus::{NmConnection, NmSettingConnection};
+
+    use super::{
+        get_stale_ovs_port_uuids, NM_SETTING_OVS_BRIDGE_SETTING_NAME,
+        NM_SETTING_OVS_PORT_SETTING_NAME,
+    };
+    use crate::Interface;
+
+    fn gen_nm_conn(
+        name: &str,
+        iface_type: &str,
+        controller: Option<&str>,
+    ) -> NmConnection {
+        NmConnection {
+            connection: Some(NmSettingConnection {
+                id: Some(name.to_string()),
+                uuid: Some(format!("uuid-{}", name)),
+                iface_type: Some(iface_type.to_string()),
+                iface_name: Some(name.to_string()),
+                controller: controller.map(|c| c.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn gen_exist_nm_conns() -> Vec<NmConnection> {
+        vec![
+            gen_nm_conn("br0", NM_SETTING_OVS_BRIDGE_SETTING_NAME, None),
+            gen_nm_conn(
+                "ovs-port-eth1",
+                NM_SETTING_OVS_PORT_SETTING_NAME,
+                Some("br0"),
+            ),
+            // Controller referred by UUID
+            gen_nm_conn(
+                "ovs-port-eth2",
+                NM_SETTING_OVS_PORT_SETTING_NAME,
+                Some("uuid-br0"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_stale_ovs_port_detached() {
+        let exist_nm_conns = gen_exist_nm_conns();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "ovs-bridge",
+            "bridge": {"port": [{"name": "eth1"}]}
+        }))
+        .unwrap();
+        assert_eq!(
+            get_stale_ovs_port_uuids(&iface, &exist_nm_conns),
+            vec!["uuid-ovs-port-eth2"]
+        );
+    }
+
+    #[test]
+    fn test_stale_ovs_port_ports_not_mentioned() {
+        let exist_nm_conns = gen_exist_nm_conns();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "ovs-bridge",
+            "bridge": {"options": {"fail-mode": "secure"}}
+        }))
+        .unwrap();
+        assert!(get_stale_ovs_port_uuids(&iface, &exist_nm_conns).is_empty());
+    }
+
+    #[test]
+    fn test_stale_ovs_port_bridge_absent() {
+        let exist_nm_conns = gen_exist_nm_conns();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "br0",
+            "type": "ovs-bridge",
+            "state": "absent"
+        }))
+        .unwrap();
+        assert_eq!(
+            get_stale_ovs_port_uuids(&iface, &exist_nm_conns),
+            vec!["uuid-ovs-port-eth1", "uuid-ovs-port-eth2"]
+        );
+    }
+
+    #[test]
+    fn test_stale_ovs_port_port_absent() {
+        let exist_nm_conns = gen_exist_nm_conns();
+        let iface: Interface = serde_json::from_value(serde_json::json!({
+            "name": "eth1",
+            "type": "ethernet",
+            "state": "absent"
+        }))
+        .unwrap();
+        assert_eq!(
+            get_stale_ovs_port_uuids(&iface, &exist_nm_conns),
+            vec!["uuid-ovs-port-eth1"]
+        );
+    }
+}
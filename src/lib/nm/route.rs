@@ -0,0 +1,52 @@
+use nm_dbus::NmIpRoute;
+
+use crate::{ErrorKind, NmstateError, RouteEntry};
+
+// NM stores routes in the IP setting of their next hop interface
+pub(crate) fn gen_nm_ip_routes(
+    routes: &[RouteEntry],
+    iface_name: &str,
+    is_ipv6: bool,
+) -> Result<Vec<NmIpRoute>, NmstateError> {
+    let mut ret = Vec::new();
+    for route in routes.iter().filter(|route| {
+        route.next_hop_iface.as_deref() == Some(iface_name)
+            && route.is_ipv6() == is_ipv6
+    }) {
+        let (dest, prefix) = match route.destination.as_deref() {
+            Some(dst) => parse_ip_prefix(dst)?,
+            None => {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("Route {} has no destination defined", route),
+                ));
+            }
+        };
+        ret.push(NmIpRoute {
+            dest: Some(dest),
+            prefix: Some(prefix),
+            next_hop: route.next_hop_addr.clone(),
+            metric: route.metric,
+            table: route.table_id,
+        });
+    }
+    Ok(ret)
+}
+
+// Split IP prefix string like `192.0.2.0/24` into address and prefix length.
+// IP address without prefix length is treated as host address.
+pub(crate) fn parse_ip_prefix(
+    ip_prefix: &str,
+) -> Result<(String, u32), NmstateError> {
+    match ip_prefix.split_once('/') {
+        Some((addr, prefix)) => match prefix.parse::<u32>() {
+            Ok(prefix) => Ok((addr.to_string(), prefix)),
+            Err(e) => Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Invalid prefix length of {}: {}", ip_prefix, e),
+            )),
+        },
+        None if ip_prefix.contains(':') => Ok((ip_prefix.to_string(), 128)),
+        None => Ok((ip_prefix.to_string(), 32)),
+    }
+}
@@ -0,0 +1,127 @@
+use std::convert::TryFrom;
+
+use nm_dbus::NmIpRouteRule;
+
+use crate::{
+    nm::route::parse_ip_prefix, ErrorKind, NmstateError, RouteEntry,
+    RouteRuleAction, RouteRuleEntry,
+};
+
+const AF_INET: i32 = 2;
+const AF_INET6: i32 = 10;
+
+const FR_ACT_TO_TBL: u8 = 1;
+const FR_ACT_BLACKHOLE: u8 = 6;
+const FR_ACT_UNREACHABLE: u8 = 7;
+const FR_ACT_PROHIBIT: u8 = 8;
+
+const ROUTE_TABLE_MAIN: u32 = 254;
+
+// NM stores route rules in the IP setting of interface holding routes of the
+// same route table.
+pub(crate) fn gen_nm_ip_rules(
+    rules: &[RouteRuleEntry],
+    routes: &[RouteEntry],
+    iface_name: &str,
+    is_ipv6: bool,
+) -> Result<Vec<NmIpRouteRule>, NmstateError> {
+    let mut ret = Vec::new();
+    for rule in rules {
+        if rule.is_ipv6() != is_ipv6
+            || rule.get_holder_iface(routes) != Some(iface_name)
+        {
+            continue;
+        }
+        // NM requires priority for every route rule, generating one would
+        // change on every apply as it depends on the rule list, hence
+        // require user to define it.
+        let priority = match rule.priority {
+            Some(p) => p,
+            None => {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Route rule {} has no priority defined which is \
+                        required by NetworkManager",
+                        rule
+                    ),
+                ));
+            }
+        };
+        let mut nm_rule = NmIpRouteRule {
+            family: Some(if is_ipv6 { AF_INET6 } else { AF_INET }),
+            priority: Some(priority),
+            iifname: rule.iif.clone(),
+            fwmark: rule.fwmark,
+            fwmask: rule.fwmask,
+            ..Default::default()
+        };
+        if let Some(ip_from) = rule.ip_from.as_deref() {
+            let (addr, prefix) = parse_ip_prefix(ip_from)?;
+            nm_rule.from = Some(addr);
+            nm_rule.from_len = Some(prefix_to_u8(ip_from, prefix)?);
+        }
+        if let Some(ip_to) = rule.ip_to.as_deref() {
+            let (addr, prefix) = parse_ip_prefix(ip_to)?;
+            nm_rule.to = Some(addr);
+            nm_rule.to_len = Some(prefix_to_u8(ip_to, prefix)?);
+        }
+        match rule.action {
+            None => {
+                nm_rule.action = Some(FR_ACT_TO_TBL);
+                nm_rule.table =
+                    Some(rule.route_table.unwrap_or(ROUTE_TABLE_MAIN));
+            }
+            Some(RouteRuleAction::Blackhole) => {
+                nm_rule.action = Some(FR_ACT_BLACKHOLE);
+            }
+            Some(RouteRuleAction::Unreachable) => {
+                nm_rule.action = Some(FR_ACT_UNREACHABLE);
+            }
+            Some(RouteRuleAction::Prohibit) => {
+                nm_rule.action = Some(FR_ACT_PROHIBIT);
+            }
+        }
+        ret.push(nm_rule);
+    }
+    Ok(ret)
+}
+
+fn prefix_to_u8(ip_prefix: &str, prefix: u32) -> Result<u8, NmstateError> {
+    u8::try_from(prefix).or_else(|_| {
+        Err(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Invalid prefix length of {}", ip_prefix),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nm_ip_rules_require_priority() {
+        let routes = vec![RouteEntry {
+            destination: Some("198.51.100.0/24".to_string()),
+            next_hop_iface: Some("eth1".to_string()),
+            ..Default::default()
+        }];
+        let mut rule = RouteRuleEntry {
+            ip_from: Some("192.0.2.1".to_string()),
+            ..Default::default()
+        };
+        let e = gen_nm_ip_rules(&[rule.clone()], &routes, "eth1", false)
+            .unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+
+        rule.priority = Some(1000);
+        let nm_rules =
+            gen_nm_ip_rules(&[rule], &routes, "eth1", false).unwrap();
+        assert_eq!(nm_rules.len(), 1);
+        assert_eq!(nm_rules[0].priority, Some(1000));
+        assert_eq!(nm_rules[0].from.as_deref(), Some("192.0.2.1"));
+        assert_eq!(nm_rules[0].from_len, Some(32));
+        assert_eq!(nm_rules[0].table, Some(ROUTE_TABLE_MAIN));
+    }
+}
@@ -1,14 +1,45 @@
+use std::convert::TryFrom;
+
 use log::warn;
 use nm_dbus::{NmApi, NmConnection, NmSettingIp, NmSettingIpMethod};
 
 use crate::{
-    nm::error::nm_error_to_nmstate, BaseInterface, EthernetInterface,
-    Interface, InterfaceIpv4, InterfaceIpv6, InterfaceState, InterfaceType,
-    LinuxBridgeInterface, NetworkState, NmstateError, UnknownInterface,
+    nm::dns::nm_dns_get,
+    nm::error::nm_error_to_nmstate,
+    nm::ip::nm_addr_gen_mode_to_nms,
+    nm::ovs::{
+        nm_ovs_bridge_conf_get, NM_SETTING_OVS_BRIDGE_SETTING_NAME,
+        NM_SETTING_OVS_IFACE_SETTING_NAME,
+    },
+    BaseInterface, BondInterface, DummyInterface, EthernetInterface, Interface,
+    InterfaceIpv4, InterfaceIpv6, InterfaceState, InterfaceType,
+    LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeMulticastRouterType,
+    LinuxBridgeOptions, LinuxBridgeStpOptions, MacVlanConfig, MacVlanInterface,
+    MacVlanMode, MacVtapConfig, MacVtapInterface, NetworkState, NmstateError,
+    OvsBridgeInterface, OvsInterface, UnknownInterface, VethConfig,
+    VethInterface, VlanInterface, VlanProtocol, VrfInterface, VxlanInterface,
 };
 
+const NM_SETTING_BOND_SETTING_NAME: &str = "bond";
 const NM_SETTING_BRIDGE_SETTING_NAME: &str = "bridge";
-const NM_SETTING_WIRED_SETTING_NAME: &str = "802-3-ethernet";
+const NM_SETTING_DUMMY_SETTING_NAME: &str = "dummy";
+const NM_SETTING_MACVLAN_SETTING_NAME: &str = "macvlan";
+pub(crate) const NM_SETTING_WIRED_SETTING_NAME: &str = "802-3-ethernet";
+const NM_SETTING_VETH_SETTING_NAME: &str = "veth";
+const NM_SETTING_VLAN_SETTING_NAME: &str = "vlan";
+const NM_SETTING_VRF_SETTING_NAME: &str = "vrf";
+const NM_SETTING_VXLAN_SETTING_NAME: &str = "vxlan";
+
+const NM_AUTO_PROP_LIST: [&str; 8] = [
+    "auto_dns",
+    "auto_gateway",
+    "auto_routes",
+    "auto_table_id",
+    "auto_route_metric",
+    "dhcp_send_hostname",
+    "dhcp_custom_hostname",
+    "dhcp_timeout",
+];
 
 pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
     let mut net_state = NetworkState::new();
@@ -17,13 +48,15 @@ pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
     let nm_conns = nm_api
         .nm_applied_connections_get()
         .or_else(|ref nm_error| Err(nm_error_to_nmstate(nm_error)))?;
-    for nm_conn in nm_conns {
-        if let Some(base_iface) = nm_conn_to_base_iface(&nm_conn) {
+    net_state.dns.config = Some(nm_dns_get(&nm_conns));
+    net_state.prop_list.push("dns");
+    for nm_conn in &nm_conns {
+        if let Some(base_iface) = nm_conn_to_base_iface(nm_conn) {
             let iface = match &base_iface.iface_type {
                 InterfaceType::LinuxBridge => {
                     Interface::LinuxBridge(LinuxBridgeInterface {
                         base: base_iface,
-                        ..Default::default()
+                        bridge: nm_linux_bridge_conf_get(nm_conn),
                     })
                 }
                 InterfaceType::Ethernet => {
@@ -32,6 +65,62 @@ pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
                         ..Default::default()
                     })
                 }
+                InterfaceType::Veth => Interface::Veth(VethInterface::new(
+                    base_iface,
+                    nm_conn.veth.as_ref().and_then(|v| v.peer.as_ref()).map(
+                        |peer| VethConfig {
+                            peer: peer.to_string(),
+                        },
+                    ),
+                )),
+                InterfaceType::Bond => Interface::Bond(BondInterface {
+                    base: base_iface,
+                    ..Default::default()
+                }),
+                InterfaceType::Dummy => {
+                    Interface::Dummy(DummyInterface::new(base_iface))
+                }
+                InterfaceType::MacVlan => {
+                    Interface::MacVlan(MacVlanInterface {
+                        base: base_iface,
+                        mac_vlan: nm_mac_vlan_conf_get(nm_conn),
+                    })
+                }
+                InterfaceType::MacVtap => {
+                    Interface::MacVtap(MacVtapInterface {
+                        base: base_iface,
+                        mac_vtap: nm_mac_vlan_conf_get(nm_conn).map(|conf| {
+                            MacVtapConfig {
+                                base_iface: conf.base_iface,
+                                mode: conf.mode,
+                                promiscuous: conf.promiscuous,
+                            }
+                        }),
+                    })
+                }
+                InterfaceType::Vlan => Interface::Vlan(VlanInterface {
+                    base: base_iface,
+                    ..Default::default()
+                }),
+                InterfaceType::OvsBridge => {
+                    Interface::OvsBridge(OvsBridgeInterface {
+                        base: base_iface,
+                        bridge: Some(nm_ovs_bridge_conf_get(
+                            nm_conn, &nm_conns,
+                        )),
+                    })
+                }
+                InterfaceType::OvsInterface => {
+                    Interface::OvsInterface(OvsInterface::new(base_iface))
+                }
+                InterfaceType::Vrf => Interface::Vrf(VrfInterface {
+                    base: base_iface,
+                    ..Default::default()
+                }),
+                InterfaceType::Vxlan => Interface::Vxlan(VxlanInterface {
+                    base: base_iface,
+                    ..Default::default()
+                }),
                 _ => Interface::Unknown(UnknownInterface::new(base_iface)),
             };
             net_state.append_interface_data(iface);
@@ -40,10 +129,134 @@ pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
     Ok(net_state)
 }
 
+// The bridge ports are not stored in bridge profile
+fn nm_linux_bridge_conf_get(
+    nm_conn: &NmConnection,
+) -> Option<LinuxBridgeConfig> {
+    let nm_br_set = nm_conn.bridge.as_ref()?;
+    let iface_name = nm_conn.iface_name().unwrap_or_default();
+    Some(LinuxBridgeConfig {
+        options: Some(LinuxBridgeOptions {
+            stp: Some(LinuxBridgeStpOptions {
+                enabled: nm_br_set.stp,
+                forward_delay: nm_br_opt_get(
+                    iface_name,
+                    "forward-delay",
+                    nm_br_set.forward_delay,
+                ),
+                hello_time: nm_br_opt_get(
+                    iface_name,
+                    "hello-time",
+                    nm_br_set.hello_time,
+                ),
+                max_age: nm_br_opt_get(
+                    iface_name,
+                    "max-age",
+                    nm_br_set.max_age,
+                ),
+                priority: nm_br_opt_get(
+                    iface_name,
+                    "priority",
+                    nm_br_set.priority,
+                ),
+            }),
+            group_forward_mask: nm_br_opt_get(
+                iface_name,
+                "group-forward-mask",
+                nm_br_set.group_forward_mask,
+            ),
+            hash_max: nm_br_set.multicast_hash_max,
+            mac_ageing_time: nm_br_set.ageing_time,
+            multicast_last_member_count: nm_br_set.multicast_last_member_count,
+            multicast_last_member_interval: nm_br_set
+                .multicast_last_member_interval,
+            multicast_membership_interval: nm_br_set
+                .multicast_membership_interval,
+            multicast_querier: nm_br_set.multicast_querier,
+            multicast_querier_interval: nm_br_set.multicast_querier_interval,
+            multicast_query_interval: nm_br_set.multicast_query_interval,
+            multicast_query_response_interval: nm_br_set
+                .multicast_query_response_interval,
+            multicast_query_use_ifaddr: nm_br_set.multicast_query_use_ifaddr,
+            multicast_router: match nm_br_set.multicast_router.as_deref() {
+                Some("disabled") => {
+                    Some(LinuxBridgeMulticastRouterType::Disabled)
+                }
+                Some("auto") => Some(LinuxBridgeMulticastRouterType::Auto),
+                Some("enabled") => {
+                    Some(LinuxBridgeMulticastRouterType::Enabled)
+                }
+                _ => None,
+            },
+            multicast_snooping: nm_br_set.multicast_snooping,
+            multicast_startup_query_count: nm_br_set
+                .multicast_startup_query_count,
+            multicast_startup_query_interval: nm_br_set
+                .multicast_startup_query_interval,
+            vlan_filtering: nm_br_set.vlan_filtering,
+            // NM is using upper case Q for 802.1Q
+            vlan_protocol: match nm_br_set.vlan_protocol.as_deref() {
+                Some("802.1Q") => Some(VlanProtocol::Ieee8021Q),
+                Some("802.1ad") => Some(VlanProtocol::Ieee8021Ad),
+                _ => None,
+            },
+        }),
+        port: None,
+    })
+}
+
+// Out of range values are ignored with a warning, the kernel values of the
+// bridge are shown instead.
+fn nm_br_opt_get<T: TryFrom<u32>>(
+    iface_name: &str,
+    opt_name: &str,
+    nm_value: Option<u32>,
+) -> Option<T> {
+    let nm_value = nm_value?;
+    match T::try_from(nm_value) {
+        Ok(v) => Some(v),
+        Err(_) => {
+            warn!(
+                "Ignoring out of range NM bridge option {} {} of \
+                interface {}",
+                opt_name, nm_value, iface_name
+            );
+            None
+        }
+    }
+}
+
+// NM stores MacVtap in macvlan setting also
+fn nm_mac_vlan_conf_get(nm_conn: &NmConnection) -> Option<MacVlanConfig> {
+    let nm_mac_vlan = nm_conn.mac_vlan.as_ref()?;
+    Some(MacVlanConfig {
+        base_iface: nm_mac_vlan.parent.as_ref()?.to_string(),
+        mode: match nm_mac_vlan.mode {
+            Some(1) => MacVlanMode::Vepa,
+            Some(2) => MacVlanMode::Bridge,
+            Some(3) => MacVlanMode::Private,
+            Some(4) => MacVlanMode::Passthru,
+            Some(5) => MacVlanMode::Source,
+            _ => MacVlanMode::Unknown,
+        },
+        // NM enables promiscuous mode by default
+        promiscuous: Some(nm_mac_vlan.promiscuous.unwrap_or(true)),
+    })
+}
+
 fn nm_iface_type_to_nmstate(nm_iface_type: &str) -> InterfaceType {
     match nm_iface_type {
         NM_SETTING_WIRED_SETTING_NAME => InterfaceType::Ethernet,
+        NM_SETTING_VETH_SETTING_NAME => InterfaceType::Veth,
         NM_SETTING_BRIDGE_SETTING_NAME => InterfaceType::LinuxBridge,
+        NM_SETTING_BOND_SETTING_NAME => InterfaceType::Bond,
+        NM_SETTING_DUMMY_SETTING_NAME => InterfaceType::Dummy,
+        NM_SETTING_MACVLAN_SETTING_NAME => InterfaceType::MacVlan,
+        NM_SETTING_VLAN_SETTING_NAME => InterfaceType::Vlan,
+        NM_SETTING_OVS_BRIDGE_SETTING_NAME => InterfaceType::OvsBridge,
+        NM_SETTING_OVS_IFACE_SETTING_NAME => InterfaceType::OvsInterface,
+        NM_SETTING_VRF_SETTING_NAME => InterfaceType::Vrf,
+        NM_SETTING_VXLAN_SETTING_NAME => InterfaceType::Vxlan,
         _ => InterfaceType::Unknown,
     }
 }
@@ -51,6 +264,13 @@ fn nm_iface_type_to_nmstate(nm_iface_type: &str) -> InterfaceType {
 fn nm_conn_to_base_iface(nm_conn: &NmConnection) -> Option<BaseInterface> {
     if let Some(iface_name) = nm_conn.iface_name() {
         if let Some(iface_type) = nm_conn.iface_type() {
+            let mut iface_type = nm_iface_type_to_nmstate(iface_type);
+            // NM stores MacVtap as macvlan setting with tap enabled
+            if iface_type == InterfaceType::MacVlan
+                && nm_conn.mac_vlan.as_ref().and_then(|m| m.tap) == Some(true)
+            {
+                iface_type = InterfaceType::MacVtap;
+            }
             let ipv4 = if let Some(ref nm_ipv4_setting) = nm_conn.ipv4 {
                 Some(nm_ip_setting_to_nmstate4(nm_ipv4_setting))
             } else {
@@ -66,7 +286,7 @@ fn nm_conn_to_base_iface(nm_conn: &NmConnection) -> Option<BaseInterface> {
                 name: iface_name.to_string(),
                 prop_list: vec!["name", "state", "iface_type", "ipv4", "ipv6"],
                 state: InterfaceState::Up,
-                iface_type: nm_iface_type_to_nmstate(iface_type),
+                iface_type,
                 ipv4: ipv4,
                 ipv6: ipv6,
                 ..Default::default()
@@ -89,12 +309,27 @@ fn nm_ip_setting_to_nmstate4(nm_ip_setting: &NmSettingIp) -> InterfaceIpv4 {
                 (true, false)
             }
         };
-        InterfaceIpv4 {
+        let mut ip = InterfaceIpv4 {
             enabled,
             dhcp,
             prop_list: vec!["enabled", "dhcp"],
             ..Default::default()
+        };
+        if dhcp {
+            ip.auto_dns = Some(nm_auto_dns_get(nm_ip_setting));
+            ip.auto_gateway = Some(nm_auto_gateway_get(nm_ip_setting));
+            ip.auto_routes = Some(nm_auto_routes_get(nm_ip_setting));
+            ip.auto_table_id = Some(nm_ip_setting.route_table.unwrap_or(0));
+            ip.auto_route_metric = nm_auto_route_metric_get(nm_ip_setting);
+            ip.dhcp_client_id = nm_ip_setting.dhcp_client_id.clone();
+            ip.dhcp_send_hostname =
+                Some(nm_dhcp_send_hostname_get(nm_ip_setting));
+            ip.dhcp_custom_hostname = nm_ip_setting.dhcp_hostname.clone();
+            ip.dhcp_timeout = nm_ip_setting.dhcp_timeout;
+            ip.prop_list.extend(NM_AUTO_PROP_LIST);
+            ip.prop_list.push("dhcp_client_id");
         }
+        ip
     } else {
         InterfaceIpv4::default()
     }
@@ -106,19 +341,67 @@ fn nm_ip_setting_to_nmstate6(nm_ip_setting: &NmSettingIp) -> InterfaceIpv6 {
             NmSettingIpMethod::Disabled => (false, false, false),
             NmSettingIpMethod::LinkLocal
             | NmSettingIpMethod::Manual
-            | NmSettingIpMethod::Shared => (true, false, true),
+            | NmSettingIpMethod::Shared => (true, false, false),
             NmSettingIpMethod::Auto => (true, true, true),
             NmSettingIpMethod::Dhcp => (true, true, false),
+            // Kernel does IPv6 autoconf when NM ignores the IPv6
             NmSettingIpMethod::Ignore => (true, false, true),
         };
-        InterfaceIpv6 {
+        let mut ip = InterfaceIpv6 {
             enabled,
             dhcp,
             autoconf,
             prop_list: vec!["enabled", "dhcp", "autoconf"],
             ..Default::default()
+        };
+        if dhcp || autoconf {
+            ip.auto_dns = Some(nm_auto_dns_get(nm_ip_setting));
+            ip.auto_gateway = Some(nm_auto_gateway_get(nm_ip_setting));
+            ip.auto_routes = Some(nm_auto_routes_get(nm_ip_setting));
+            ip.auto_table_id = Some(nm_ip_setting.route_table.unwrap_or(0));
+            ip.auto_route_metric = nm_auto_route_metric_get(nm_ip_setting);
+            ip.dhcp_duid = nm_ip_setting.dhcp_duid.clone();
+            ip.dhcp_send_hostname =
+                Some(nm_dhcp_send_hostname_get(nm_ip_setting));
+            ip.dhcp_custom_hostname = nm_ip_setting.dhcp_hostname.clone();
+            ip.dhcp_timeout = nm_ip_setting.dhcp_timeout;
+            ip.prop_list.extend(NM_AUTO_PROP_LIST);
+            ip.prop_list.push("dhcp_duid");
         }
+        if autoconf {
+            ip.addr_gen_mode =
+                nm_addr_gen_mode_to_nms(nm_ip_setting.addr_gen_mode);
+            ip.token = nm_ip_setting.token.clone();
+            ip.prop_list.push("addr_gen_mode");
+            ip.prop_list.push("token");
+        }
+        ip
     } else {
         InterfaceIpv6::default()
     }
 }
+
+// NM omits properties holding default value, hence the NM default value is
+// used when property not found.
+fn nm_auto_dns_get(nm_ip_setting: &NmSettingIp) -> bool {
+    !nm_ip_setting.ignore_auto_dns.unwrap_or(false)
+}
+
+fn nm_auto_gateway_get(nm_ip_setting: &NmSettingIp) -> bool {
+    !nm_ip_setting.never_default.unwrap_or(false)
+}
+
+fn nm_auto_routes_get(nm_ip_setting: &NmSettingIp) -> bool {
+    !nm_ip_setting.ignore_auto_routes.unwrap_or(false)
+}
+
+fn nm_dhcp_send_hostname_get(nm_ip_setting: &NmSettingIp) -> bool {
+    nm_ip_setting.dhcp_send_hostname.unwrap_or(true)
+}
+
+// NM use -1 for default route metric
+fn nm_auto_route_metric_get(nm_ip_setting: &NmSettingIp) -> Option<u32> {
+    nm_ip_setting
+        .route_metric
+        .and_then(|m| u32::try_from(m).ok())
+}
@@ -0,0 +1,329 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ip::{sanitize_ip_addr, sanitize_ip_network},
+    ErrorKind, NmstateError,
+};
+
+const ROUTE_TABLE_MAIN: u32 = 254;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Routes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running: Option<Vec<RouteEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<Vec<RouteEntry>>,
+}
+
+impl Routes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_none() && self.config.is_none()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        for route in self.config.as_deref().unwrap_or(&[]) {
+            if route.is_absent() {
+                continue;
+            }
+            if route.destination.is_none() {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("Route {} has no destination defined", route),
+                ));
+            }
+            if route.next_hop_iface.is_none() {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Route {} has no next-hop-interface defined",
+                        route
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn sanitize(&mut self) -> Result<(), NmstateError> {
+        if let Some(routes) = self.config.as_mut() {
+            for route in routes {
+                route.sanitize()?;
+            }
+        }
+        Ok(())
+    }
+
+    // Only the `config` section is verified, `running` routes might be
+    // created or removed by others at any time.
+    pub(crate) fn verify(&self, current: &Self) -> Result<(), NmstateError> {
+        let cur_routes = current.config.as_deref().unwrap_or(&[]);
+        for route in self.config.as_deref().unwrap_or(&[]) {
+            if route.is_absent() {
+                if let Some(cur_route) =
+                    cur_routes.iter().find(|r| route.is_match(r))
+                {
+                    return Err(NmstateError::new(
+                        ErrorKind::VerificationError,
+                        format!(
+                            "Desired absent route {} still found as {}",
+                            route, cur_route
+                        ),
+                    ));
+                }
+            } else if !cur_routes.iter().any(|r| route.is_match(r)) {
+                return Err(NmstateError::new(
+                    ErrorKind::VerificationError,
+                    format!("Failed to find desired route {}", route),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // The route config after desire state applied on top of current:
+    //  * Remove current routes matching any absent route.
+    //  * Append desired routes not found in current.
+    pub(crate) fn gen_merged_config(&self, current: &Self) -> Vec<RouteEntry> {
+        let cur_routes = current.config.as_deref().unwrap_or(&[]);
+        let des_routes = match self.config.as_deref() {
+            Some(r) => r,
+            None => return cur_routes.to_vec(),
+        };
+        let absent_routes: Vec<&RouteEntry> =
+            des_routes.iter().filter(|r| r.is_absent()).collect();
+
+        let mut merged_routes: Vec<RouteEntry> = cur_routes
+            .iter()
+            .filter(|cur_route| {
+                !absent_routes.iter().any(|r| r.is_match(cur_route))
+            })
+            .cloned()
+            .collect();
+        for route in des_routes.iter().filter(|r| !r.is_absent()) {
+            if !merged_routes.iter().any(|r| route.is_match(r)) {
+                merged_routes.push(route.clone());
+            }
+        }
+        merged_routes
+    }
+
+    // Names of interfaces holding routes added or removed by merged routes
+    pub(crate) fn get_changed_ifaces<'a>(
+        &'a self,
+        merged_routes: &'a [RouteEntry],
+    ) -> HashSet<&'a str> {
+        let cur_routes = self.config.as_deref().unwrap_or(&[]);
+        let mut ret = HashSet::new();
+        for route in merged_routes
+            .iter()
+            .filter(|r| !cur_routes.contains(r))
+            .chain(cur_routes.iter().filter(|r| !merged_routes.contains(r)))
+        {
+            if let Some(iface_name) = route.next_hop_iface.as_ref() {
+                ret.insert(iface_name.as_str());
+            }
+        }
+        ret
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RouteState {
+    Absent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RouteEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<RouteState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        rename = "next-hop-interface"
+    )]
+    pub next_hop_iface: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        rename = "next-hop-address"
+    )]
+    pub next_hop_addr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_id: Option<u32>,
+}
+
+impl std::fmt::Display for RouteEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string(self)
+                .unwrap_or_else(|_| format!("{:?}", self))
+        )
+    }
+}
+
+impl RouteEntry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn is_absent(&self) -> bool {
+        self.state == Some(RouteState::Absent)
+    }
+
+    pub(crate) fn is_ipv6(&self) -> bool {
+        self.destination.as_ref().map(|d| d.contains(':')) == Some(true)
+    }
+
+    // Canonicalize the IP addresses, so that `is_match()` could compare
+    // them as string.
+    pub(crate) fn sanitize(&mut self) -> Result<(), NmstateError> {
+        if let Some(dst) = self.destination.as_deref() {
+            self.destination = Some(sanitize_ip_network(dst)?);
+        }
+        if let Some(via) = self.next_hop_addr.as_deref() {
+            self.next_hop_addr = Some(sanitize_ip_addr(via)?);
+        }
+        Ok(())
+    }
+
+    // Properties not defined in self are treated as wildcard, except the
+    // table ID of non-absent route which is main route table by default.
+    pub(crate) fn is_match(&self, other: &Self) -> bool {
+        if self.destination.is_some() && self.destination != other.destination {
+            return false;
+        }
+        if self.next_hop_iface.is_some()
+            && self.next_hop_iface != other.next_hop_iface
+        {
+            return false;
+        }
+        if self.next_hop_addr.is_some()
+            && self.next_hop_addr != other.next_hop_addr
+        {
+            return false;
+        }
+        if self.metric.is_some() && self.metric != other.metric {
+            return false;
+        }
+        if self.is_absent() {
+            if self.table_id.is_some() && self.table_id != other.table_id {
+                return false;
+            }
+        } else if self.table_id.unwrap_or(ROUTE_TABLE_MAIN)
+            != other.table_id.unwrap_or(ROUTE_TABLE_MAIN)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gen_route(dst: &str, iface: &str, via: &str) -> RouteEntry {
+        let mut route = RouteEntry {
+            destination: Some(dst.to_string()),
+            next_hop_iface: Some(iface.to_string()),
+            next_hop_addr: Some(via.to_string()),
+            ..Default::default()
+        };
+        route.sanitize().unwrap();
+        route
+    }
+
+    #[test]
+    fn test_route_sanitize() {
+        let route = gen_route("2001:DB8:0:0::1/64", "eth1", "2001:DB8::0:FE");
+        assert_eq!(route.destination.as_deref(), Some("2001:db8::/64"));
+        assert_eq!(route.next_hop_addr.as_deref(), Some("2001:db8::fe"));
+        let route = gen_route("192.0.2.1", "eth1", "198.51.100.1");
+        assert_eq!(route.destination.as_deref(), Some("192.0.2.1/32"));
+        let route = gen_route("2001:db8::1", "eth1", "2001:db8::fe");
+        assert_eq!(route.destination.as_deref(), Some("2001:db8::1/128"));
+        let mut route = RouteEntry {
+            destination: Some("192.0.2.0/33".to_string()),
+            ..Default::default()
+        };
+        assert!(route.sanitize().is_err());
+    }
+
+    #[test]
+    fn test_route_is_match() {
+        let route = gen_route("2001:db8:1::/64", "eth1", "2001:db8::fe");
+        let mut cur_route =
+            gen_route("2001:db8:1:0::/64", "eth1", "2001:db8:0::fe");
+        cur_route.table_id = Some(ROUTE_TABLE_MAIN);
+        cur_route.metric = Some(100);
+        assert!(route.is_match(&cur_route));
+
+        let mut other_table_route = cur_route.clone();
+        other_table_route.table_id = Some(100);
+        assert!(!route.is_match(&other_table_route));
+
+        let absent_route = RouteEntry {
+            state: Some(RouteState::Absent),
+            next_hop_iface: Some("eth1".to_string()),
+            ..Default::default()
+        };
+        assert!(absent_route.is_match(&other_table_route));
+        assert!(!absent_route.is_match(&gen_route(
+            "2001:db8:1::/64",
+            "eth2",
+            "2001:db8::fe"
+        )));
+    }
+
+    #[test]
+    fn test_route_gen_merged_config() {
+        let current = Routes {
+            config: Some(vec![
+                gen_route("192.0.2.0/24", "eth1", "198.51.100.1"),
+                gen_route("2001:db8:1::/64", "eth1", "2001:db8::fe"),
+                gen_route("2001:db8:2::/64", "eth2", "2001:db8::fe"),
+            ]),
+            ..Default::default()
+        };
+        let desired = Routes {
+            config: Some(vec![
+                RouteEntry {
+                    state: Some(RouteState::Absent),
+                    next_hop_iface: Some("eth1".to_string()),
+                    ..Default::default()
+                },
+                gen_route("2001:DB8:2::/64", "eth2", "2001:db8::FE"),
+                gen_route("203.0.113.0/24", "eth2", "198.51.100.1"),
+            ]),
+            ..Default::default()
+        };
+        let merged = desired.gen_merged_config(&current);
+        assert_eq!(
+            merged,
+            vec![
+                gen_route("2001:db8:2::/64", "eth2", "2001:db8::fe"),
+                gen_route("203.0.113.0/24", "eth2", "198.51.100.1"),
+            ]
+        );
+        let changed_ifaces = current.get_changed_ifaces(&merged);
+        assert_eq!(changed_ifaces, vec!["eth1", "eth2"].into_iter().collect());
+
+        assert_eq!(
+            Routes::default().gen_merged_config(&current),
+            current.config.unwrap()
+        );
+    }
+}
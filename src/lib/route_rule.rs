@@ -0,0 +1,366 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ErrorKind, NmstateError, RouteEntry};
+
+const ROUTE_TABLE_MAIN: u32 = 254;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RouteRules {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<Vec<RouteRuleEntry>>,
+}
+
+impl RouteRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.config.is_none()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        for rule in self.config.as_deref().unwrap_or(&[]) {
+            if rule.is_absent() {
+                continue;
+            }
+            if rule.ip_from.is_none()
+                && rule.ip_to.is_none()
+                && rule.family.is_none()
+            {
+                return Err(NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Route rule {} has none of ip-from, ip-to or family \
+                        defined",
+                        rule
+                    ),
+                ));
+            }
+            if let (Some(ip_from), Some(ip_to)) = (&rule.ip_from, &rule.ip_to) {
+                if ip_from.contains(':') != ip_to.contains(':') {
+                    return Err(NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Route rule {} has ip-from and ip-to in \
+                            different IP family",
+                            rule
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn verify(&self, current: &Self) -> Result<(), NmstateError> {
+        let cur_rules = current.config.as_deref().unwrap_or(&[]);
+        for rule in self.config.as_deref().unwrap_or(&[]) {
+            if rule.is_absent() {
+                if let Some(cur_rule) =
+                    cur_rules.iter().find(|r| rule.is_match(r))
+                {
+                    return Err(NmstateError::new(
+                        ErrorKind::VerificationError,
+                        format!(
+                            "Desired absent route rule {} still found as {}",
+                            rule, cur_rule
+                        ),
+                    ));
+                }
+            } else if !cur_rules.iter().any(|r| rule.is_match(r)) {
+                return Err(NmstateError::new(
+                    ErrorKind::VerificationError,
+                    format!("Failed to find desired route rule {}", rule),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // The route rule config after desire state applied on top of current:
+    //  * Remove current rules matching any absent rule.
+    //  * Append desired rules not found in current.
+    pub(crate) fn gen_merged_config(
+        &self,
+        current: &Self,
+    ) -> Vec<RouteRuleEntry> {
+        let cur_rules = current.config.as_deref().unwrap_or(&[]);
+        let des_rules = match self.config.as_deref() {
+            Some(r) => r,
+            None => return cur_rules.to_vec(),
+        };
+        let absent_rules: Vec<&RouteRuleEntry> =
+            des_rules.iter().filter(|r| r.is_absent()).collect();
+
+        let mut merged_rules: Vec<RouteRuleEntry> = cur_rules
+            .iter()
+            .filter(|cur_rule| {
+                !absent_rules.iter().any(|r| r.is_match(cur_rule))
+            })
+            .cloned()
+            .collect();
+        for rule in des_rules.iter().filter(|r| !r.is_absent()) {
+            if !merged_rules.iter().any(|r| rule.is_match(r)) {
+                merged_rules.push(rule.clone());
+            }
+        }
+        merged_rules
+    }
+
+    // Names of interfaces holding rules added or removed by merged rules.
+    // Added rules without any holder interface is considered as error.
+    pub(crate) fn get_changed_ifaces<'a>(
+        &'a self,
+        merged_rules: &'a [RouteRuleEntry],
+        cur_routes: &'a [RouteEntry],
+        merged_routes: &'a [RouteEntry],
+    ) -> Result<HashSet<&'a str>, NmstateError> {
+        let cur_rules = self.config.as_deref().unwrap_or(&[]);
+        let mut ret = HashSet::new();
+        for rule in merged_rules.iter().filter(|r| !cur_rules.contains(r)) {
+            match rule.get_holder_iface(merged_routes) {
+                Some(iface_name) => {
+                    ret.insert(iface_name);
+                }
+                None => {
+                    return Err(NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Failed to find interface with route table {} \
+                            for route rule {}",
+                            rule.route_table.unwrap_or(ROUTE_TABLE_MAIN),
+                            rule
+                        ),
+                    ));
+                }
+            }
+        }
+        for rule in cur_rules.iter().filter(|r| !merged_rules.contains(r)) {
+            if let Some(iface_name) = rule.get_holder_iface(cur_routes) {
+                ret.insert(iface_name);
+            }
+        }
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RouteRuleState {
+    Absent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RouteRuleFamily {
+    Ipv4,
+    Ipv6,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RouteRuleAction {
+    Blackhole,
+    Unreachable,
+    Prohibit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RouteRuleEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<RouteRuleState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<RouteRuleFamily>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_table: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iif: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fwmark: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fwmask: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<RouteRuleAction>,
+}
+
+impl std::fmt::Display for RouteRuleEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string(self)
+                .unwrap_or_else(|_| format!("{:?}", self))
+        )
+    }
+}
+
+impl RouteRuleEntry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn is_absent(&self) -> bool {
+        self.state == Some(RouteRuleState::Absent)
+    }
+
+    pub(crate) fn is_ipv6(&self) -> bool {
+        match self.family {
+            Some(family) => family == RouteRuleFamily::Ipv6,
+            None => {
+                self.ip_from.as_ref().map(|i| i.contains(':')) == Some(true)
+                    || self.ip_to.as_ref().map(|i| i.contains(':'))
+                        == Some(true)
+            }
+        }
+    }
+
+    // Properties not defined in self are treated as wildcard, except the
+    // route table of non-absent rule which is main route table by default.
+    pub(crate) fn is_match(&self, other: &Self) -> bool {
+        if (self.family.is_some()
+            || self.ip_from.is_some()
+            || self.ip_to.is_some())
+            && self.is_ipv6() != other.is_ipv6()
+        {
+            return false;
+        }
+        if self.ip_from.is_some() && self.ip_from != other.ip_from {
+            return false;
+        }
+        if self.ip_to.is_some() && self.ip_to != other.ip_to {
+            return false;
+        }
+        if self.priority.is_some() && self.priority != other.priority {
+            return false;
+        }
+        if self.iif.is_some() && self.iif != other.iif {
+            return false;
+        }
+        if self.fwmark.is_some() && self.fwmark != other.fwmark {
+            return false;
+        }
+        if self.fwmask.is_some() && self.fwmask != other.fwmask {
+            return false;
+        }
+        if self.action.is_some() && self.action != other.action {
+            return false;
+        }
+        if self.is_absent() {
+            if self.route_table.is_some()
+                && self.route_table != other.route_table
+            {
+                return false;
+            }
+        } else if self.action.is_none()
+            && self.route_table.unwrap_or(ROUTE_TABLE_MAIN)
+                != other.route_table.unwrap_or(ROUTE_TABLE_MAIN)
+        {
+            return false;
+        }
+        true
+    }
+
+    // Route rule has no interface binding, but some plugins like
+    // NetworkManager require it to be stored in a interface holding
+    // routes of the same route table and IP family.
+    // Rule with action like blackhole is not using any route table, hence
+    // it could be stored in any interface holding routes of the same IP
+    // family.
+    pub(crate) fn get_holder_iface<'a>(
+        &self,
+        routes: &'a [RouteEntry],
+    ) -> Option<&'a str> {
+        let table_id = self.route_table.unwrap_or(ROUTE_TABLE_MAIN);
+        let mut iface_names: Vec<&str> = routes
+            .iter()
+            .filter(|route| {
+                (self.action.is_some()
+                    || route.table_id.unwrap_or(ROUTE_TABLE_MAIN) == table_id)
+                    && route.is_ipv6() == self.is_ipv6()
+            })
+            .filter_map(|route| route.next_hop_iface.as_deref())
+            .collect();
+        // Sort to make sure the same interface is chosen every time
+        iface_names.sort_unstable();
+        iface_names.first().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gen_routes() -> Vec<RouteEntry> {
+        vec![
+            RouteEntry {
+                destination: Some("198.51.100.0/24".to_string()),
+                next_hop_iface: Some("eth2".to_string()),
+                table_id: Some(100),
+                ..Default::default()
+            },
+            RouteEntry {
+                destination: Some("2001:db8::/64".to_string()),
+                next_hop_iface: Some("eth1".to_string()),
+                table_id: Some(ROUTE_TABLE_MAIN),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_route_rule_holder_iface_of_table() {
+        let routes = gen_routes();
+        let mut rule = RouteRuleEntry {
+            ip_from: Some("192.0.2.0/24".to_string()),
+            route_table: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(rule.get_holder_iface(&routes), Some("eth2"));
+        rule.route_table = None;
+        assert_eq!(rule.get_holder_iface(&routes), None);
+    }
+
+    #[test]
+    fn test_route_rule_holder_iface_of_action_rule() {
+        let routes = gen_routes();
+        let rule = RouteRuleEntry {
+            ip_from: Some("192.0.2.0/24".to_string()),
+            action: Some(RouteRuleAction::Blackhole),
+            ..Default::default()
+        };
+        assert_eq!(rule.get_holder_iface(&routes), Some("eth2"));
+
+        let cur_rules = RouteRules::default();
+        let merged_rules = vec![rule];
+        let changed_ifaces = cur_rules
+            .get_changed_ifaces(&merged_rules, &[], &routes)
+            .unwrap();
+        assert_eq!(changed_ifaces, vec!["eth2"].into_iter().collect());
+    }
+
+    #[test]
+    fn test_route_rule_no_holder_iface() {
+        let routes = gen_routes();
+        let cur_rules = RouteRules::default();
+        let merged_rules = vec![RouteRuleEntry {
+            ip_to: Some("2001:db8:1::/64".to_string()),
+            route_table: Some(100),
+            ..Default::default()
+        }];
+        let e = cur_rules
+            .get_changed_ifaces(&merged_rules, &[], &routes)
+            .unwrap_err();
+        assert!(matches!(e.kind(), ErrorKind::InvalidArgument));
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::{dbus_value::value_hash_get_string_hash, error::NmError};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmSettingBond {
+    pub options: HashMap<String, String>,
+}
+
+impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingBond {
+    type Error = NmError;
+    fn try_from(
+        value: &HashMap<String, zvariant::OwnedValue>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            options: value_hash_get_string_hash(value, "options")?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl NmSettingBond {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        let mut options = zvariant::Dict::new(
+            zvariant::Signature::from_str_unchecked("s"),
+            zvariant::Signature::from_str_unchecked("s"),
+        );
+        for (k, v) in self.options.iter() {
+            options.append(
+                zvariant::Value::new(k.as_str()),
+                zvariant::Value::new(v.as_str()),
+            )?;
+        }
+        ret.insert("options", zvariant::Value::Dict(options));
+        Ok(ret)
+    }
+}
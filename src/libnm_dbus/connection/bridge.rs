@@ -16,11 +16,39 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
 
-use crate::{dbus_value::value_hash_get_bool, error::NmError};
+use crate::{
+    dbus_value::{
+        value_dict_get_bool, value_dict_get_u16, value_hash_get_array,
+        value_hash_get_bool, value_hash_get_string, value_hash_get_u32,
+        value_hash_get_u64,
+    },
+    error::{ErrorKind, NmError},
+};
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct NmSettingBridge {
     pub stp: Option<bool>,
+    pub priority: Option<u32>,
+    pub forward_delay: Option<u32>,
+    pub hello_time: Option<u32>,
+    pub max_age: Option<u32>,
+    pub ageing_time: Option<u32>,
+    pub group_forward_mask: Option<u32>,
+    pub multicast_hash_max: Option<u32>,
+    pub multicast_last_member_count: Option<u32>,
+    pub multicast_last_member_interval: Option<u64>,
+    pub multicast_membership_interval: Option<u64>,
+    pub multicast_querier: Option<bool>,
+    pub multicast_querier_interval: Option<u64>,
+    pub multicast_query_interval: Option<u64>,
+    pub multicast_query_response_interval: Option<u64>,
+    pub multicast_query_use_ifaddr: Option<bool>,
+    pub multicast_router: Option<String>,
+    pub multicast_snooping: Option<bool>,
+    pub multicast_startup_query_count: Option<u32>,
+    pub multicast_startup_query_interval: Option<u64>,
+    pub vlan_filtering: Option<bool>,
+    pub vlan_protocol: Option<String>,
 }
 
 impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingBridge {
@@ -30,6 +58,63 @@ impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingBridge {
     ) -> Result<Self, Self::Error> {
         Ok(Self {
             stp: value_hash_get_bool(value, "stp")?,
+            priority: value_hash_get_u32(value, "priority")?,
+            forward_delay: value_hash_get_u32(value, "forward-delay")?,
+            hello_time: value_hash_get_u32(value, "hello-time")?,
+            max_age: value_hash_get_u32(value, "max-age")?,
+            ageing_time: value_hash_get_u32(value, "ageing-time")?,
+            group_forward_mask: value_hash_get_u32(
+                value,
+                "group-forward-mask",
+            )?,
+            multicast_hash_max: value_hash_get_u32(
+                value,
+                "multicast-hash-max",
+            )?,
+            multicast_last_member_count: value_hash_get_u32(
+                value,
+                "multicast-last-member-count",
+            )?,
+            multicast_last_member_interval: value_hash_get_u64(
+                value,
+                "multicast-last-member-interval",
+            )?,
+            multicast_membership_interval: value_hash_get_u64(
+                value,
+                "multicast-membership-interval",
+            )?,
+            multicast_querier: value_hash_get_bool(value, "multicast-querier")?,
+            multicast_querier_interval: value_hash_get_u64(
+                value,
+                "multicast-querier-interval",
+            )?,
+            multicast_query_interval: value_hash_get_u64(
+                value,
+                "multicast-query-interval",
+            )?,
+            multicast_query_response_interval: value_hash_get_u64(
+                value,
+                "multicast-query-response-interval",
+            )?,
+            multicast_query_use_ifaddr: value_hash_get_bool(
+                value,
+                "multicast-query-use-ifaddr",
+            )?,
+            multicast_router: value_hash_get_string(value, "multicast-router")?,
+            multicast_snooping: value_hash_get_bool(
+                value,
+                "multicast-snooping",
+            )?,
+            multicast_startup_query_count: value_hash_get_u32(
+                value,
+                "multicast-startup-query-count",
+            )?,
+            multicast_startup_query_interval: value_hash_get_u64(
+                value,
+                "multicast-startup-query-interval",
+            )?,
+            vlan_filtering: value_hash_get_bool(value, "vlan-filtering")?,
+            vlan_protocol: value_hash_get_string(value, "vlan-protocol")?,
         })
     }
 }
@@ -42,21 +127,126 @@ impl NmSettingBridge {
         if let Some(v) = self.stp {
             ret.insert("stp", zvariant::Value::new(v));
         }
+        if let Some(v) = self.priority {
+            ret.insert("priority", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.forward_delay {
+            ret.insert("forward-delay", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.hello_time {
+            ret.insert("hello-time", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.max_age {
+            ret.insert("max-age", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ageing_time {
+            ret.insert("ageing-time", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.group_forward_mask {
+            ret.insert("group-forward-mask", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_hash_max {
+            ret.insert("multicast-hash-max", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_last_member_count {
+            ret.insert("multicast-last-member-count", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_last_member_interval {
+            ret.insert(
+                "multicast-last-member-interval",
+                zvariant::Value::new(v),
+            );
+        }
+        if let Some(v) = self.multicast_membership_interval {
+            ret.insert(
+                "multicast-membership-interval",
+                zvariant::Value::new(v),
+            );
+        }
+        if let Some(v) = self.multicast_querier {
+            ret.insert("multicast-querier", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_querier_interval {
+            ret.insert("multicast-querier-interval", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_query_interval {
+            ret.insert("multicast-query-interval", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_query_response_interval {
+            ret.insert(
+                "multicast-query-response-interval",
+                zvariant::Value::new(v),
+            );
+        }
+        if let Some(v) = self.multicast_query_use_ifaddr {
+            ret.insert("multicast-query-use-ifaddr", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.multicast_router {
+            ret.insert("multicast-router", zvariant::Value::new(v.as_str()));
+        }
+        if let Some(v) = self.multicast_snooping {
+            ret.insert("multicast-snooping", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_startup_query_count {
+            ret.insert(
+                "multicast-startup-query-count",
+                zvariant::Value::new(v),
+            );
+        }
+        if let Some(v) = self.multicast_startup_query_interval {
+            ret.insert(
+                "multicast-startup-query-interval",
+                zvariant::Value::new(v),
+            );
+        }
+        if let Some(v) = self.vlan_filtering {
+            ret.insert("vlan-filtering", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.vlan_protocol {
+            ret.insert("vlan-protocol", zvariant::Value::new(v.as_str()));
+        }
         Ok(ret)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct NmSettingBridgePort {
-    // TODO: bridge port vlan filter.
+    pub hairpin_mode: Option<bool>,
+    pub path_cost: Option<u32>,
+    pub priority: Option<u32>,
+    pub vlans: Option<Vec<NmSettingBridgeVlanRange>>,
 }
 
 impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingBridgePort {
     type Error = NmError;
     fn try_from(
-        _value: &HashMap<String, zvariant::OwnedValue>,
+        value: &HashMap<String, zvariant::OwnedValue>,
     ) -> Result<Self, Self::Error> {
-        Ok(Self {})
+        Ok(Self {
+            hairpin_mode: value_hash_get_bool(value, "hairpin-mode")?,
+            path_cost: value_hash_get_u32(value, "path-cost")?,
+            priority: value_hash_get_u32(value, "priority")?,
+            vlans: if let Some(nm_vlans) = value_hash_get_array(value, "vlans")?
+            {
+                let mut vlans = Vec::new();
+                for nm_vlan in nm_vlans.iter() {
+                    let nm_vlan = <&zvariant::Dict>::try_from(nm_vlan)
+                        .map_err(|e| {
+                            NmError::new(
+                                ErrorKind::InvalidArgument,
+                                format!(
+                                    "Invalid bridge port VLAN {:?}: {}",
+                                    nm_vlan, e
+                                ),
+                            )
+                        })?;
+                    vlans.push(NmSettingBridgeVlanRange::try_from(nm_vlan)?);
+                }
+                Some(vlans)
+            } else {
+                None
+            },
+        })
     }
 }
 
@@ -64,6 +254,78 @@ impl NmSettingBridgePort {
     pub(crate) fn to_value(
         &self,
     ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
-        Ok(HashMap::new())
+        let mut ret = HashMap::new();
+        if let Some(v) = self.hairpin_mode {
+            ret.insert("hairpin-mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.path_cost {
+            ret.insert("path-cost", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.priority {
+            ret.insert("priority", zvariant::Value::new(v));
+        }
+        if let Some(vlans) = &self.vlans {
+            let mut vlans_value = zvariant::Array::new(
+                zvariant::Signature::from_str_unchecked("a{sv}"),
+            );
+            for vlan in vlans {
+                vlans_value.append(vlan.to_value()?)?;
+            }
+            ret.insert("vlans", zvariant::Value::Array(vlans_value));
+        }
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmSettingBridgeVlanRange {
+    pub vid_start: u16,
+    pub vid_end: u16,
+    pub pvid: bool,
+    pub untagged: bool,
+}
+
+impl TryFrom<&zvariant::Dict<'_, '_>> for NmSettingBridgeVlanRange {
+    type Error = NmError;
+    fn try_from(value: &zvariant::Dict) -> Result<Self, Self::Error> {
+        let vid_start = value_dict_get_u16(value, "vid-start")?.unwrap_or(0);
+        Ok(Self {
+            vid_start,
+            vid_end: value_dict_get_u16(value, "vid-end")?.unwrap_or(vid_start),
+            pvid: value_dict_get_bool(value, "pvid")?.unwrap_or(false),
+            untagged: value_dict_get_bool(value, "untagged")?.unwrap_or(false),
+        })
+    }
+}
+
+impl NmSettingBridgeVlanRange {
+    fn to_value(&self) -> Result<zvariant::Value, NmError> {
+        let mut ret = zvariant::Dict::new(
+            zvariant::Signature::from_str_unchecked("s"),
+            zvariant::Signature::from_str_unchecked("v"),
+        );
+        ret.append(
+            zvariant::Value::new("vid-start".to_string()),
+            zvariant::Value::Value(Box::new(zvariant::Value::U16(
+                self.vid_start,
+            ))),
+        )?;
+        ret.append(
+            zvariant::Value::new("vid-end".to_string()),
+            zvariant::Value::Value(Box::new(zvariant::Value::U16(
+                self.vid_end,
+            ))),
+        )?;
+        ret.append(
+            zvariant::Value::new("pvid".to_string()),
+            zvariant::Value::Value(Box::new(zvariant::Value::Bool(self.pvid))),
+        )?;
+        ret.append(
+            zvariant::Value::new("untagged".to_string()),
+            zvariant::Value::Value(Box::new(zvariant::Value::Bool(
+                self.untagged,
+            ))),
+        )?;
+        Ok(zvariant::Value::Dict(ret))
     }
 }
@@ -17,8 +17,18 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use crate::{
+    connection::bond::NmSettingBond,
     connection::bridge::{NmSettingBridge, NmSettingBridgePort},
+    connection::ethtool::NmSettingEthtool,
     connection::ip::NmSettingIp,
+    connection::keyfile::nm_conn_to_keyfile,
+    connection::mac_vlan::NmSettingMacVlan,
+    connection::ovs::{NmSettingOvsBridge, NmSettingOvsIface},
+    connection::veth::NmSettingVeth,
+    connection::vlan::NmSettingVlan,
+    connection::vrf::NmSettingVrf,
+    connection::vxlan::NmSettingVxlan,
+    connection::wired::NmSettingWired,
     dbus_value::{
         value_hash_get_bool, value_hash_get_i32, value_hash_get_string,
     },
@@ -40,10 +50,20 @@ pub(crate) type NmConnectionDbusValue<'a> =
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct NmConnection {
     pub connection: Option<NmSettingConnection>,
+    pub bond: Option<NmSettingBond>,
     pub bridge: Option<NmSettingBridge>,
     pub bridge_port: Option<NmSettingBridgePort>,
+    pub ethtool: Option<NmSettingEthtool>,
     pub ipv4: Option<NmSettingIp>,
     pub ipv6: Option<NmSettingIp>,
+    pub mac_vlan: Option<NmSettingMacVlan>,
+    pub ovs_bridge: Option<NmSettingOvsBridge>,
+    pub ovs_iface: Option<NmSettingOvsIface>,
+    pub veth: Option<NmSettingVeth>,
+    pub vlan: Option<NmSettingVlan>,
+    pub vrf: Option<NmSettingVrf>,
+    pub vxlan: Option<NmSettingVxlan>,
+    pub wired: Option<NmSettingWired>,
 }
 
 impl TryFrom<NmConnectionDbusOwnedValue> for NmConnection {
@@ -62,6 +82,9 @@ impl TryFrom<NmConnectionDbusOwnedValue> for NmConnection {
         if let Some(ipv6_set) = value.get("ipv6") {
             nm_con.ipv6 = Some(NmSettingIp::try_from(ipv6_set)?);
         }
+        if let Some(bond_value) = value.get("bond") {
+            nm_con.bond = Some(NmSettingBond::try_from(bond_value)?);
+        }
         if let Some(br_value) = value.get("bridge") {
             nm_con.bridge = Some(NmSettingBridge::try_from(br_value)?);
         }
@@ -69,6 +92,35 @@ impl TryFrom<NmConnectionDbusOwnedValue> for NmConnection {
             nm_con.bridge_port =
                 Some(NmSettingBridgePort::try_from(br_port_value)?);
         }
+        if let Some(ethtool_value) = value.get("ethtool") {
+            nm_con.ethtool = Some(NmSettingEthtool::try_from(ethtool_value)?);
+        }
+        if let Some(mac_vlan_value) = value.get("macvlan") {
+            nm_con.mac_vlan = Some(NmSettingMacVlan::try_from(mac_vlan_value)?);
+        }
+        if let Some(ovs_br_value) = value.get("ovs-bridge") {
+            nm_con.ovs_bridge =
+                Some(NmSettingOvsBridge::try_from(ovs_br_value)?);
+        }
+        if let Some(ovs_iface_value) = value.get("ovs-interface") {
+            nm_con.ovs_iface =
+                Some(NmSettingOvsIface::try_from(ovs_iface_value)?);
+        }
+        if let Some(veth_value) = value.get("veth") {
+            nm_con.veth = Some(NmSettingVeth::try_from(veth_value)?);
+        }
+        if let Some(vlan_value) = value.get("vlan") {
+            nm_con.vlan = Some(NmSettingVlan::try_from(vlan_value)?);
+        }
+        if let Some(vrf_value) = value.get("vrf") {
+            nm_con.vrf = Some(NmSettingVrf::try_from(vrf_value)?);
+        }
+        if let Some(vxlan_value) = value.get("vxlan") {
+            nm_con.vxlan = Some(NmSettingVxlan::try_from(vxlan_value)?);
+        }
+        if let Some(wired_value) = value.get("802-3-ethernet") {
+            nm_con.wired = Some(NmSettingWired::try_from(wired_value)?);
+        }
         Ok(nm_con)
     }
 }
@@ -98,23 +150,77 @@ impl NmConnection {
         }
     }
 
+    pub fn controller(&self) -> Option<&str> {
+        if let Some(NmSettingConnection {
+            controller: Some(controller),
+            ..
+        }) = &self.connection
+        {
+            Some(controller.as_str())
+        } else {
+            None
+        }
+    }
+
+    pub fn controller_type(&self) -> Option<&str> {
+        if let Some(NmSettingConnection {
+            controller_type: Some(controller_type),
+            ..
+        }) = &self.connection
+        {
+            Some(controller_type.as_str())
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn to_value(&self) -> Result<NmConnectionDbusValue, NmError> {
         let mut ret = HashMap::new();
         if let Some(con_set) = &self.connection {
             ret.insert("connection", con_set.to_value()?);
         }
+        if let Some(bond_set) = &self.bond {
+            ret.insert("bond", bond_set.to_value()?);
+        }
         if let Some(br_set) = &self.bridge {
             ret.insert("bridge", br_set.to_value()?);
         }
         if let Some(br_port_set) = &self.bridge_port {
             ret.insert("bridge-port", br_port_set.to_value()?);
         }
+        if let Some(ethtool_set) = &self.ethtool {
+            ret.insert("ethtool", ethtool_set.to_value()?);
+        }
         if let Some(ipv4_set) = &self.ipv4 {
             ret.insert("ipv4", ipv4_set.to_value()?);
         }
         if let Some(ipv6_set) = &self.ipv6 {
             ret.insert("ipv6", ipv6_set.to_value()?);
         }
+        if let Some(mac_vlan_set) = &self.mac_vlan {
+            ret.insert("macvlan", mac_vlan_set.to_value()?);
+        }
+        if let Some(ovs_br_set) = &self.ovs_bridge {
+            ret.insert("ovs-bridge", ovs_br_set.to_value()?);
+        }
+        if let Some(ovs_iface_set) = &self.ovs_iface {
+            ret.insert("ovs-interface", ovs_iface_set.to_value()?);
+        }
+        if let Some(veth_set) = &self.veth {
+            ret.insert("veth", veth_set.to_value()?);
+        }
+        if let Some(vlan_set) = &self.vlan {
+            ret.insert("vlan", vlan_set.to_value()?);
+        }
+        if let Some(vrf_set) = &self.vrf {
+            ret.insert("vrf", vrf_set.to_value()?);
+        }
+        if let Some(vxlan_set) = &self.vxlan {
+            ret.insert("vxlan", vxlan_set.to_value()?);
+        }
+        if let Some(wired_set) = &self.wired {
+            ret.insert("802-3-ethernet", wired_set.to_value()?);
+        }
         Ok(ret)
     }
 
@@ -126,6 +232,10 @@ impl NmConnection {
         }
         None
     }
+
+    pub fn to_keyfile(&self) -> Result<String, NmError> {
+        nm_conn_to_keyfile(self)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -0,0 +1,109 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::{
+    dbus_value::{value_hash_get_bool, value_hash_get_u32},
+    error::NmError,
+};
+
+const NM_ETHTOOL_FEATURE_PREFIX: &str = "feature-";
+const NM_ETHTOOL_COALESCE_PREFIX: &str = "coalesce-";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmSettingEthtool {
+    pub pause_autoneg: Option<bool>,
+    pub pause_rx: Option<bool>,
+    pub pause_tx: Option<bool>,
+    pub ring_rx: Option<u32>,
+    pub ring_rx_jumbo: Option<u32>,
+    pub ring_rx_mini: Option<u32>,
+    pub ring_tx: Option<u32>,
+    // NM option name of coalesce with `coalesce-` prefix, like
+    // `coalesce-rx-usecs`, NM stores adaptive coalesce as u32 also.
+    pub coalesce: HashMap<String, u32>,
+    // NM option name of feature with `feature-` prefix, like `feature-tso`
+    pub features: HashMap<String, bool>,
+}
+
+impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingEthtool {
+    type Error = NmError;
+    fn try_from(
+        value: &HashMap<String, zvariant::OwnedValue>,
+    ) -> Result<Self, Self::Error> {
+        let mut features = HashMap::new();
+        let mut coalesce = HashMap::new();
+        for key in value.keys() {
+            if key.starts_with(NM_ETHTOOL_FEATURE_PREFIX) {
+                if let Some(enabled) = value_hash_get_bool(value, key)? {
+                    features.insert(key.to_string(), enabled);
+                }
+            } else if key.starts_with(NM_ETHTOOL_COALESCE_PREFIX) {
+                if let Some(v) = value_hash_get_u32(value, key)? {
+                    coalesce.insert(key.to_string(), v);
+                }
+            }
+        }
+        Ok(Self {
+            pause_autoneg: value_hash_get_bool(value, "pause-autoneg")?,
+            pause_rx: value_hash_get_bool(value, "pause-rx")?,
+            pause_tx: value_hash_get_bool(value, "pause-tx")?,
+            ring_rx: value_hash_get_u32(value, "ring-rx")?,
+            ring_rx_jumbo: value_hash_get_u32(value, "ring-rx-jumbo")?,
+            ring_rx_mini: value_hash_get_u32(value, "ring-rx-mini")?,
+            ring_tx: value_hash_get_u32(value, "ring-tx")?,
+            coalesce,
+            features,
+        })
+    }
+}
+
+impl NmSettingEthtool {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = self.pause_autoneg {
+            ret.insert("pause-autoneg", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.pause_rx {
+            ret.insert("pause-rx", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.pause_tx {
+            ret.insert("pause-tx", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ring_rx {
+            ret.insert("ring-rx", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ring_rx_jumbo {
+            ret.insert("ring-rx-jumbo", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ring_rx_mini {
+            ret.insert("ring-rx-mini", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ring_tx {
+            ret.insert("ring-tx", zvariant::Value::new(v));
+        }
+        for (k, v) in self.coalesce.iter() {
+            ret.insert(k.as_str(), zvariant::Value::new(*v));
+        }
+        for (k, v) in self.features.iter() {
+            ret.insert(k.as_str(), zvariant::Value::new(*v));
+        }
+        Ok(ret)
+    }
+}
@@ -15,11 +15,14 @@
 
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use crate::{
     dbus_value::{
-        value_dict_get_string, value_dict_get_u32, value_hash_get_array,
-        value_hash_get_string,
+        value_dict_get_i32, value_dict_get_string, value_dict_get_u32,
+        value_dict_get_u8, value_hash_get_array, value_hash_get_bool,
+        value_hash_get_i32, value_hash_get_i64, value_hash_get_string,
+        value_hash_get_string_array, value_hash_get_u32,
     },
     error::{ErrorKind, NmError},
 };
@@ -82,6 +85,24 @@ impl TryFrom<&str> for NmSettingIpMethod {
 pub struct NmSettingIp {
     pub method: Option<NmSettingIpMethod>,
     pub addresses: Vec<String>,
+    pub routes: Vec<NmIpRoute>,
+    pub route_rules: Vec<NmIpRouteRule>,
+    pub dns: Option<Vec<String>>,
+    pub dns_search: Option<Vec<String>>,
+    pub dns_options: Option<Vec<String>>,
+    pub dns_priority: Option<i32>,
+    pub ignore_auto_dns: Option<bool>,
+    pub ignore_auto_routes: Option<bool>,
+    pub never_default: Option<bool>,
+    pub route_table: Option<u32>,
+    pub route_metric: Option<i64>,
+    pub dhcp_client_id: Option<String>, // IPv4 only
+    pub dhcp_duid: Option<String>,      // IPv6 only
+    pub dhcp_send_hostname: Option<bool>,
+    pub dhcp_hostname: Option<String>,
+    pub dhcp_timeout: Option<i32>,
+    pub addr_gen_mode: Option<i32>, // IPv6 only
+    pub token: Option<String>,      // IPv6 only
 }
 
 impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingIp {
@@ -114,7 +135,55 @@ impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingIp {
                 }
             }
         }
-        Ok(Self { method, addresses })
+        let mut routes = Vec::new();
+        if let Some(nm_routes) = value_hash_get_array(value, "route-data")? {
+            for nm_route in nm_routes.iter() {
+                if let Ok(nm_route) = <&zvariant::Dict>::try_from(nm_route) {
+                    routes.push(NmIpRoute::try_from(nm_route)?);
+                }
+            }
+        }
+        let mut route_rules = Vec::new();
+        if let Some(nm_rules) = value_hash_get_array(value, "routing-rules")? {
+            for nm_rule in nm_rules.iter() {
+                if let Ok(nm_rule) = <&zvariant::Dict>::try_from(nm_rule) {
+                    route_rules.push(NmIpRouteRule::try_from(nm_rule)?);
+                }
+            }
+        }
+        let dns = if let Some(nm_dns) = value_hash_get_array(value, "dns")? {
+            Some(parse_nm_dns(nm_dns)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            method,
+            addresses,
+            routes,
+            route_rules,
+            dns,
+            dns_search: value_hash_get_string_array(value, "dns-search")?,
+            dns_options: value_hash_get_string_array(value, "dns-options")?,
+            dns_priority: value_hash_get_i32(value, "dns-priority")?,
+            ignore_auto_dns: value_hash_get_bool(value, "ignore-auto-dns")?,
+            ignore_auto_routes: value_hash_get_bool(
+                value,
+                "ignore-auto-routes",
+            )?,
+            never_default: value_hash_get_bool(value, "never-default")?,
+            route_table: value_hash_get_u32(value, "route-table")?,
+            route_metric: value_hash_get_i64(value, "route-metric")?,
+            dhcp_client_id: value_hash_get_string(value, "dhcp-client-id")?,
+            dhcp_duid: value_hash_get_string(value, "dhcp-duid")?,
+            dhcp_send_hostname: value_hash_get_bool(
+                value,
+                "dhcp-send-hostname",
+            )?,
+            dhcp_hostname: value_hash_get_string(value, "dhcp-hostname")?,
+            dhcp_timeout: value_hash_get_i32(value, "dhcp-timeout")?,
+            addr_gen_mode: value_hash_get_i32(value, "addr-gen-mode")?,
+            token: value_hash_get_string(value, "token")?,
+        })
     }
 }
 
@@ -166,6 +235,322 @@ impl NmSettingIp {
             addresss_data.append(zvariant::Value::Dict(addr_dict))?;
         }
         ret.insert("address-data", zvariant::Value::Array(addresss_data));
+        let mut route_data = zvariant::Array::new(
+            zvariant::Signature::from_str_unchecked("a{sv}"),
+        );
+        for route in &self.routes {
+            route_data.append(route.to_value()?)?;
+        }
+        ret.insert("route-data", zvariant::Value::Array(route_data));
+        let mut routing_rules = zvariant::Array::new(
+            zvariant::Signature::from_str_unchecked("a{sv}"),
+        );
+        for rule in &self.route_rules {
+            routing_rules.append(rule.to_value()?)?;
+        }
+        ret.insert("routing-rules", zvariant::Value::Array(routing_rules));
+        // Empty name server list has no IP family to generate the signature
+        if let Some(v) = self.dns.as_ref().filter(|v| !v.is_empty()) {
+            ret.insert("dns", gen_nm_dns_value(v)?);
+        }
+        if let Some(v) = &self.dns_search {
+            ret.insert("dns-search", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = &self.dns_options {
+            ret.insert("dns-options", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = self.dns_priority {
+            ret.insert("dns-priority", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ignore_auto_dns {
+            ret.insert("ignore-auto-dns", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ignore_auto_routes {
+            ret.insert("ignore-auto-routes", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.never_default {
+            ret.insert("never-default", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.route_table {
+            ret.insert("route-table", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.route_metric {
+            ret.insert("route-metric", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.dhcp_client_id {
+            ret.insert("dhcp-client-id", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = &self.dhcp_duid {
+            ret.insert("dhcp-duid", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = self.dhcp_send_hostname {
+            ret.insert("dhcp-send-hostname", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.dhcp_hostname {
+            ret.insert("dhcp-hostname", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = self.dhcp_timeout {
+            ret.insert("dhcp-timeout", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.addr_gen_mode {
+            ret.insert("addr-gen-mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.token {
+            ret.insert("token", zvariant::Value::new(v.clone()));
+        }
         Ok(ret)
     }
 }
+
+// NM stores IPv4 name servers as `au` in network byte order and IPv6 name
+// servers as `aay`.
+fn parse_nm_dns(nm_dns: &zvariant::Array) -> Result<Vec<String>, NmError> {
+    let mut ret = Vec::new();
+    for nm_srv in nm_dns.iter() {
+        if let Ok(ipv4) = u32::try_from(nm_srv.clone()) {
+            ret.push(Ipv4Addr::from(ipv4.to_ne_bytes()).to_string());
+        } else if let Ok(nm_ipv6) = <&zvariant::Array>::try_from(nm_srv) {
+            let mut octets = [0u8; 16];
+            if nm_ipv6.len() != octets.len() {
+                return Err(NmError::new(
+                    ErrorKind::Bug,
+                    format!("Invalid IPv6 name server {:?}", nm_ipv6),
+                ));
+            }
+            for (i, octet) in nm_ipv6.iter().enumerate() {
+                octets[i] = u8::try_from(octet.clone()).map_err(|e| {
+                    NmError::new(
+                        ErrorKind::Bug,
+                        format!(
+                            "Invalid IPv6 name server {:?}: {}",
+                            nm_ipv6, e
+                        ),
+                    )
+                })?;
+            }
+            ret.push(Ipv6Addr::from(octets).to_string());
+        } else {
+            return Err(NmError::new(
+                ErrorKind::Bug,
+                format!("Invalid name server {:?}", nm_srv),
+            ));
+        }
+    }
+    Ok(ret)
+}
+
+fn gen_nm_dns_value(servers: &[String]) -> Result<zvariant::Value, NmError> {
+    let mut ipv4_srvs: Vec<u32> = Vec::new();
+    let mut ipv6_srvs: Vec<Vec<u8>> = Vec::new();
+    for srv in servers {
+        match srv.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => {
+                ipv4_srvs.push(u32::from_ne_bytes(ip.octets()))
+            }
+            Ok(IpAddr::V6(ip)) => ipv6_srvs.push(ip.octets().to_vec()),
+            Err(e) => {
+                return Err(NmError::new(
+                    ErrorKind::InvalidArgument,
+                    format!("Invalid name server {}: {}", srv, e),
+                ))
+            }
+        }
+    }
+    if !ipv4_srvs.is_empty() && !ipv6_srvs.is_empty() {
+        return Err(NmError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "IPv4 and IPv6 name servers cannot be stored in the same \
+                IP setting: {:?}",
+                servers
+            ),
+        ));
+    }
+    if ipv6_srvs.is_empty() {
+        Ok(zvariant::Value::new(ipv4_srvs))
+    } else {
+        Ok(zvariant::Value::new(ipv6_srvs))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmIpRoute {
+    pub dest: Option<String>,
+    pub prefix: Option<u32>,
+    pub next_hop: Option<String>,
+    pub metric: Option<u32>,
+    pub table: Option<u32>,
+}
+
+impl TryFrom<&zvariant::Dict<'_, '_>> for NmIpRoute {
+    type Error = NmError;
+    fn try_from(value: &zvariant::Dict) -> Result<Self, Self::Error> {
+        Ok(Self {
+            dest: value_dict_get_string(value, "dest")?,
+            prefix: value_dict_get_u32(value, "prefix")?,
+            next_hop: value_dict_get_string(value, "next-hop")?,
+            metric: value_dict_get_u32(value, "metric")?,
+            table: value_dict_get_u32(value, "table")?,
+        })
+    }
+}
+
+impl NmIpRoute {
+    fn to_value(&self) -> Result<zvariant::Value, NmError> {
+        let mut ret = zvariant::Dict::new(
+            zvariant::Signature::from_str_unchecked("s"),
+            zvariant::Signature::from_str_unchecked("v"),
+        );
+        if let Some(v) = &self.dest {
+            ret.append(
+                zvariant::Value::new("dest".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::new(
+                    v.to_string(),
+                ))),
+            )?;
+        }
+        if let Some(v) = self.prefix {
+            ret.append(
+                zvariant::Value::new("prefix".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U32(v))),
+            )?;
+        }
+        if let Some(v) = &self.next_hop {
+            ret.append(
+                zvariant::Value::new("next-hop".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::new(
+                    v.to_string(),
+                ))),
+            )?;
+        }
+        if let Some(v) = self.metric {
+            ret.append(
+                zvariant::Value::new("metric".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U32(v))),
+            )?;
+        }
+        if let Some(v) = self.table {
+            ret.append(
+                zvariant::Value::new("table".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U32(v))),
+            )?;
+        }
+        Ok(zvariant::Value::Dict(ret))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmIpRouteRule {
+    pub family: Option<i32>,
+    pub priority: Option<u32>,
+    pub from: Option<String>,
+    pub from_len: Option<u8>,
+    pub to: Option<String>,
+    pub to_len: Option<u8>,
+    pub table: Option<u32>,
+    pub iifname: Option<String>,
+    pub fwmark: Option<u32>,
+    pub fwmask: Option<u32>,
+    pub action: Option<u8>,
+}
+
+impl TryFrom<&zvariant::Dict<'_, '_>> for NmIpRouteRule {
+    type Error = NmError;
+    fn try_from(value: &zvariant::Dict) -> Result<Self, Self::Error> {
+        Ok(Self {
+            family: value_dict_get_i32(value, "family")?,
+            priority: value_dict_get_u32(value, "priority")?,
+            from: value_dict_get_string(value, "from")?,
+            from_len: value_dict_get_u8(value, "from-len")?,
+            to: value_dict_get_string(value, "to")?,
+            to_len: value_dict_get_u8(value, "to-len")?,
+            table: value_dict_get_u32(value, "table")?,
+            iifname: value_dict_get_string(value, "iifname")?,
+            fwmark: value_dict_get_u32(value, "fwmark")?,
+            fwmask: value_dict_get_u32(value, "fwmask")?,
+            action: value_dict_get_u8(value, "action")?,
+        })
+    }
+}
+
+impl NmIpRouteRule {
+    fn to_value(&self) -> Result<zvariant::Value, NmError> {
+        let mut ret = zvariant::Dict::new(
+            zvariant::Signature::from_str_unchecked("s"),
+            zvariant::Signature::from_str_unchecked("v"),
+        );
+        if let Some(v) = self.family {
+            ret.append(
+                zvariant::Value::new("family".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::I32(v))),
+            )?;
+        }
+        if let Some(v) = self.priority {
+            ret.append(
+                zvariant::Value::new("priority".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U32(v))),
+            )?;
+        }
+        if let Some(v) = &self.from {
+            ret.append(
+                zvariant::Value::new("from".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::new(
+                    v.to_string(),
+                ))),
+            )?;
+        }
+        if let Some(v) = self.from_len {
+            ret.append(
+                zvariant::Value::new("from-len".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U8(v))),
+            )?;
+        }
+        if let Some(v) = &self.to {
+            ret.append(
+                zvariant::Value::new("to".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::new(
+                    v.to_string(),
+                ))),
+            )?;
+        }
+        if let Some(v) = self.to_len {
+            ret.append(
+                zvariant::Value::new("to-len".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U8(v))),
+            )?;
+        }
+        if let Some(v) = self.table {
+            ret.append(
+                zvariant::Value::new("table".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U32(v))),
+            )?;
+        }
+        if let Some(v) = &self.iifname {
+            ret.append(
+                zvariant::Value::new("iifname".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::new(
+                    v.to_string(),
+                ))),
+            )?;
+        }
+        if let Some(v) = self.fwmark {
+            ret.append(
+                zvariant::Value::new("fwmark".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U32(v))),
+            )?;
+        }
+        if let Some(v) = self.fwmask {
+            ret.append(
+                zvariant::Value::new("fwmask".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U32(v))),
+            )?;
+        }
+        if let Some(v) = self.action {
+            ret.append(
+                zvariant::Value::new("action".to_string()),
+                zvariant::Value::Value(Box::new(zvariant::Value::U8(v))),
+            )?;
+        }
+        Ok(zvariant::Value::Dict(ret))
+    }
+}
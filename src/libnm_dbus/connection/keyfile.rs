@@ -0,0 +1,462 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+
+use crate::{
+    connection::bridge::NmSettingBridgeVlanRange,
+    connection::conn::NmConnection,
+    connection::ip::{NmIpRoute, NmIpRouteRule, NmSettingIp},
+    error::{ErrorKind, NmError},
+};
+
+// Settings using a different section name in keyfile than D-Bus
+const KEYFILE_SECTION_ALIASES: [(&str, &str); 1] =
+    [("802-3-ethernet", "ethernet")];
+
+// Properties using a different key name in keyfile than D-Bus:
+// (setting name, D-Bus key name, keyfile key name)
+const KEYFILE_KEY_ALIASES: [(&str, &str, &str); 1] = [(
+    "802-3-ethernet",
+    "assigned-mac-address",
+    "cloned-mac-address",
+)];
+
+// D-Bus only properties of IP setting, keyfile stores them differently
+const IP_DBUS_ONLY_KEYS: [&str; 4] =
+    ["address-data", "route-data", "routing-rules", "dns"];
+
+// Defined in linux/fib_rules.h
+const FR_ACT_BLACKHOLE: u8 = 6;
+const FR_ACT_UNREACHABLE: u8 = 7;
+const FR_ACT_PROHIBIT: u8 = 8;
+
+pub(crate) fn nm_conn_to_keyfile(
+    nm_conn: &NmConnection,
+) -> Result<String, NmError> {
+    let nm_value = nm_conn.to_value()?;
+    let mut setting_names: Vec<&str> = nm_value.keys().copied().collect();
+    // NetworkManager always place the connection section first
+    setting_names.sort_unstable_by_key(|name| (*name != "connection", *name));
+
+    let mut ret = String::new();
+    for setting_name in setting_names {
+        let setting_value = match nm_value.get(setting_name) {
+            Some(v) => v,
+            None => continue,
+        };
+        let mut keys = match setting_name {
+            "ipv4" | "ipv6" => {
+                let nm_ip_set = if setting_name == "ipv4" {
+                    nm_conn.ipv4.as_ref()
+                } else {
+                    nm_conn.ipv6.as_ref()
+                };
+                let mut keys = setting_value_to_keyfile(
+                    setting_value,
+                    &IP_DBUS_ONLY_KEYS,
+                )?;
+                if let Some(nm_ip_set) = nm_ip_set {
+                    keys.extend(ip_setting_to_keyfile(nm_ip_set));
+                }
+                keys
+            }
+            "bond" => {
+                let mut keys = Vec::new();
+                if let Some(bond_set) = nm_conn.bond.as_ref() {
+                    for (k, v) in bond_set.options.iter() {
+                        keys.push((k.to_string(), escape_keyfile_value(v)));
+                    }
+                }
+                keys
+            }
+            "bridge-port" => {
+                let mut keys =
+                    setting_value_to_keyfile(setting_value, &["vlans"])?;
+                if let Some(vlans) =
+                    nm_conn.bridge_port.as_ref().and_then(|s| s.vlans.as_ref())
+                {
+                    keys.push(("vlans".to_string(), vlans_to_keyfile(vlans)));
+                }
+                keys
+            }
+            _ => setting_value_to_keyfile(setting_value, &[])?,
+        };
+        for (k, _) in keys.iter_mut() {
+            if let Some((_, _, keyfile_key)) =
+                KEYFILE_KEY_ALIASES.iter().find(|(name, dbus_key, _)| {
+                    *name == setting_name && dbus_key == k
+                })
+            {
+                *k = keyfile_key.to_string();
+            }
+        }
+        keys.sort_unstable();
+
+        let section_name = KEYFILE_SECTION_ALIASES
+            .iter()
+            .find(|(dbus_name, _)| *dbus_name == setting_name)
+            .map(|(_, keyfile_name)| *keyfile_name)
+            .unwrap_or(setting_name);
+        if !ret.is_empty() {
+            ret.push('\n');
+        }
+        ret += &format!("[{}]\n", section_name);
+        for (k, v) in keys {
+            ret += &format!("{}={}\n", k, v);
+        }
+    }
+    Ok(ret)
+}
+
+fn setting_value_to_keyfile(
+    setting_value: &HashMap<&str, zvariant::Value>,
+    skip_keys: &[&str],
+) -> Result<Vec<(String, String)>, NmError> {
+    let mut ret = Vec::new();
+    for (k, v) in setting_value.iter() {
+        if !skip_keys.contains(k) {
+            ret.push((k.to_string(), value_to_keyfile(v)?));
+        }
+    }
+    Ok(ret)
+}
+
+// Keyfile store list as items each followed by a semicolon
+fn value_to_keyfile(value: &zvariant::Value) -> Result<String, NmError> {
+    Ok(match value {
+        zvariant::Value::Bool(v) => v.to_string(),
+        zvariant::Value::U8(v) => v.to_string(),
+        zvariant::Value::I16(v) => v.to_string(),
+        zvariant::Value::U16(v) => v.to_string(),
+        zvariant::Value::I32(v) => v.to_string(),
+        zvariant::Value::U32(v) => v.to_string(),
+        zvariant::Value::I64(v) => v.to_string(),
+        zvariant::Value::U64(v) => v.to_string(),
+        zvariant::Value::F64(v) => v.to_string(),
+        zvariant::Value::Str(v) => escape_keyfile_value(v.as_str()),
+        zvariant::Value::Value(v) => value_to_keyfile(v)?,
+        zvariant::Value::Array(items) => {
+            let mut ret = String::new();
+            for item in items.iter() {
+                ret += &value_to_keyfile(item)?.replace(';', "\\;");
+                ret.push(';');
+            }
+            ret
+        }
+        _ => {
+            return Err(NmError::new(
+                ErrorKind::Bug,
+                format!("Unsupported keyfile value {:?}", value),
+            ));
+        }
+    })
+}
+
+// Backslash is the escape character of keyfile, leading and trailing spaces
+// would be stripped without escaping. The list separator `;` is escaped by
+// value_to_keyfile() as it is only special within list.
+fn escape_keyfile_value(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\");
+    let trimmed = escaped.trim_matches(' ');
+    if trimmed.len() == escaped.len() {
+        return escaped;
+    }
+    let leading = escaped.len() - escaped.trim_start_matches(' ').len();
+    let trailing = escaped.len() - leading - trimmed.len();
+    format!(
+        "{}{}{}",
+        "\\s".repeat(leading),
+        trimmed,
+        "\\s".repeat(trailing)
+    )
+}
+
+fn ip_setting_to_keyfile(nm_ip_set: &NmSettingIp) -> Vec<(String, String)> {
+    let mut ret = Vec::new();
+    for (i, addr) in nm_ip_set.addresses.iter().enumerate() {
+        ret.push((format!("address{}", i + 1), addr.to_string()));
+    }
+    for (i, route) in nm_ip_set.routes.iter().enumerate() {
+        ret.push((format!("route{}", i + 1), route_to_keyfile(route)));
+        if let Some(table) = route.table {
+            ret.push((
+                format!("route{}_options", i + 1),
+                format!("table={}", table),
+            ));
+        }
+    }
+    for (i, rule) in nm_ip_set.route_rules.iter().enumerate() {
+        ret.push((
+            format!("routing-rule{}", i + 1),
+            route_rule_to_keyfile(rule),
+        ));
+    }
+    if let Some(srvs) = nm_ip_set.dns.as_ref().filter(|s| !s.is_empty()) {
+        ret.push((
+            "dns".to_string(),
+            srvs.iter().map(|s| format!("{};", s)).collect(),
+        ));
+    }
+    ret
+}
+
+// Format: <dest>/<prefix>[,<next_hop>[,<metric>]]
+fn route_to_keyfile(route: &NmIpRoute) -> String {
+    let mut ret = format!(
+        "{}/{}",
+        route.dest.as_deref().unwrap_or_default(),
+        route.prefix.unwrap_or_default()
+    );
+    if route.next_hop.is_some() || route.metric.is_some() {
+        ret += &format!(",{}", route.next_hop.as_deref().unwrap_or_default());
+    }
+    if let Some(metric) = route.metric {
+        ret += &format!(",{}", metric);
+    }
+    ret
+}
+
+fn route_rule_to_keyfile(rule: &NmIpRouteRule) -> String {
+    let mut items = Vec::new();
+    if let Some(v) = rule.priority {
+        items.push(format!("priority {}", v));
+    }
+    if let Some(v) = rule.from.as_ref() {
+        items.push(format!("from {}/{}", v, rule.from_len.unwrap_or_default()));
+    }
+    if let Some(v) = rule.to.as_ref() {
+        items.push(format!("to {}/{}", v, rule.to_len.unwrap_or_default()));
+    }
+    if let Some(v) = rule.iifname.as_ref() {
+        items.push(format!("iif {}", v));
+    }
+    if let Some(v) = rule.fwmark {
+        items.push(format!(
+            "fwmark {:#x}/{:#x}",
+            v,
+            rule.fwmask.unwrap_or(u32::MAX)
+        ));
+    }
+    match rule.action {
+        Some(FR_ACT_BLACKHOLE) => items.push("type blackhole".to_string()),
+        Some(FR_ACT_UNREACHABLE) => items.push("type unreachable".to_string()),
+        Some(FR_ACT_PROHIBIT) => items.push("type prohibit".to_string()),
+        _ => {
+            if let Some(v) = rule.table {
+                items.push(format!("table {}", v));
+            }
+        }
+    }
+    items.join(" ")
+}
+
+// Format: <vid_start>[-<vid_end>][ pvid][ untagged] separated by comma
+fn vlans_to_keyfile(vlans: &[NmSettingBridgeVlanRange]) -> String {
+    let mut items = Vec::new();
+    for vlan in vlans {
+        let mut item = if vlan.vid_start == vlan.vid_end {
+            vlan.vid_start.to_string()
+        } else {
+            format!("{}-{}", vlan.vid_start, vlan.vid_end)
+        };
+        if vlan.pvid {
+            item += " pvid";
+        }
+        if vlan.untagged {
+            item += " untagged";
+        }
+        items.push(item);
+    }
+    items.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        NmSettingBond, NmSettingBridgePort, NmSettingConnection,
+        NmSettingIpMethod, NmSettingVlan, NmSettingWired,
+    };
+
+    fn gen_nm_conn(iface_name: &str, iface_type: &str) -> NmConnection {
+        NmConnection {
+            connection: Some(NmSettingConnection {
+                id: Some(iface_name.to_string()),
+                uuid: Some("7b3a8e2e-5a2b-4c1a-9a4e-0d8b0f6f1c11".to_string()),
+                iface_type: Some(iface_type.to_string()),
+                iface_name: Some(iface_name.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    // Return the lines of specified section
+    fn get_section(keyfile: &str, section_name: &str) -> Vec<String> {
+        keyfile
+            .split("\n\n")
+            .find(|s| s.starts_with(&format!("[{}]\n", section_name)))
+            .map(|s| s.lines().skip(1).map(|l| l.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_keyfile_bond() {
+        let mut nm_conn = gen_nm_conn("bond0", "bond");
+        nm_conn.bond = Some(NmSettingBond {
+            options: vec![
+                ("mode".to_string(), "802.3ad".to_string()),
+                ("miimon".to_string(), "100".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        });
+        let keyfile = nm_conn_to_keyfile(&nm_conn).unwrap();
+
+        assert!(keyfile.starts_with("[connection]\n"));
+        assert!(get_section(&keyfile, "connection")
+            .contains(&"type=bond".to_string()));
+        assert_eq!(
+            get_section(&keyfile, "bond"),
+            vec!["miimon=100", "mode=802.3ad"]
+        );
+    }
+
+    #[test]
+    fn test_keyfile_vlan_with_mac() {
+        let mut nm_conn = gen_nm_conn("eth1.10", "vlan");
+        nm_conn.vlan = Some(NmSettingVlan {
+            parent: Some("eth1".to_string()),
+            id: Some(10),
+        });
+        nm_conn.wired = Some(NmSettingWired {
+            cloned_mac_address: Some("00:23:45:67:89:1a".to_string()),
+            mtu: Some(1400),
+            ..Default::default()
+        });
+        let keyfile = nm_conn_to_keyfile(&nm_conn).unwrap();
+
+        assert_eq!(get_section(&keyfile, "vlan"), vec!["id=10", "parent=eth1"]);
+        assert_eq!(
+            get_section(&keyfile, "ethernet"),
+            vec!["cloned-mac-address=00:23:45:67:89:1a", "mtu=1400"]
+        );
+        assert!(!keyfile.contains("802-3-ethernet"));
+        assert!(!keyfile.contains("assigned-mac-address"));
+    }
+
+    #[test]
+    fn test_keyfile_routes_and_rules() {
+        let mut nm_conn = gen_nm_conn("eth1", "802-3-ethernet");
+        nm_conn.ipv4 = Some(NmSettingIp {
+            method: Some(NmSettingIpMethod::Manual),
+            addresses: vec!["192.0.2.1/24".to_string()],
+            routes: vec![
+                NmIpRoute {
+                    dest: Some("198.51.100.0".to_string()),
+                    prefix: Some(24),
+                    next_hop: Some("192.0.2.254".to_string()),
+                    metric: Some(100),
+                    table: Some(200),
+                },
+                NmIpRoute {
+                    dest: Some("203.0.113.0".to_string()),
+                    prefix: Some(24),
+                    ..Default::default()
+                },
+            ],
+            route_rules: vec![
+                NmIpRouteRule {
+                    family: Some(2),
+                    priority: Some(1000),
+                    from: Some("192.0.2.0".to_string()),
+                    from_len: Some(24),
+                    table: Some(200),
+                    ..Default::default()
+                },
+                NmIpRouteRule {
+                    family: Some(2),
+                    priority: Some(1001),
+                    to: Some("198.51.100.0".to_string()),
+                    to_len: Some(24),
+                    action: Some(FR_ACT_BLACKHOLE),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+        let keyfile = nm_conn_to_keyfile(&nm_conn).unwrap();
+
+        assert_eq!(
+            get_section(&keyfile, "ipv4"),
+            vec![
+                "address1=192.0.2.1/24",
+                "method=manual",
+                "route1=198.51.100.0/24,192.0.2.254,100",
+                "route1_options=table=200",
+                "route2=203.0.113.0/24",
+                "routing-rule1=priority 1000 from 192.0.2.0/24 table 200",
+                "routing-rule2=priority 1001 to 198.51.100.0/24 \
+                type blackhole",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keyfile_bridge_port_vlans() {
+        let mut nm_conn = gen_nm_conn("eth1", "802-3-ethernet");
+        nm_conn.bridge_port = Some(NmSettingBridgePort {
+            vlans: Some(vec![
+                NmSettingBridgeVlanRange {
+                    vid_start: 1,
+                    vid_end: 1,
+                    pvid: true,
+                    untagged: true,
+                },
+                NmSettingBridgeVlanRange {
+                    vid_start: 100,
+                    vid_end: 200,
+                    pvid: false,
+                    untagged: false,
+                },
+            ]),
+            ..Default::default()
+        });
+        let keyfile = nm_conn_to_keyfile(&nm_conn).unwrap();
+
+        assert_eq!(
+            get_section(&keyfile, "bridge-port"),
+            vec!["vlans=1 pvid untagged,100-200"]
+        );
+    }
+
+    #[test]
+    fn test_keyfile_escape() {
+        assert_eq!(escape_keyfile_value("a b"), "a b");
+        assert_eq!(escape_keyfile_value(" a\\b  "), "\\sa\\\\b\\s\\s");
+
+        let mut nm_conn = gen_nm_conn("eth1", "802-3-ethernet");
+        nm_conn.ipv4 = Some(NmSettingIp {
+            method: Some(NmSettingIpMethod::Disabled),
+            dns_search: Some(vec!["a;b".to_string(), "c".to_string()]),
+            ..Default::default()
+        });
+        let keyfile = nm_conn_to_keyfile(&nm_conn).unwrap();
+
+        assert!(get_section(&keyfile, "ipv4")
+            .contains(&"dns-search=a\\;b;c;".to_string()));
+    }
+}
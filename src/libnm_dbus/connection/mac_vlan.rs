@@ -0,0 +1,67 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::{
+    dbus_value::{
+        value_hash_get_bool, value_hash_get_string, value_hash_get_u32,
+    },
+    error::NmError,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmSettingMacVlan {
+    pub parent: Option<String>,
+    pub mode: Option<u32>,
+    pub promiscuous: Option<bool>,
+    pub tap: Option<bool>,
+}
+
+impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingMacVlan {
+    type Error = NmError;
+    fn try_from(
+        value: &HashMap<String, zvariant::OwnedValue>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            parent: value_hash_get_string(value, "parent")?,
+            mode: value_hash_get_u32(value, "mode")?,
+            promiscuous: value_hash_get_bool(value, "promiscuous")?,
+            tap: value_hash_get_bool(value, "tap")?,
+        })
+    }
+}
+
+impl NmSettingMacVlan {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.parent {
+            ret.insert("parent", zvariant::Value::new(v.as_str()));
+        }
+        if let Some(v) = self.mode {
+            ret.insert("mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.promiscuous {
+            ret.insert("promiscuous", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.tap {
+            ret.insert("tap", zvariant::Value::new(v));
+        }
+        Ok(ret)
+    }
+}
@@ -13,13 +13,36 @@
 // limitations under the License.
 //
 
+mod bond;
 mod bridge;
 mod conn;
+mod ethtool;
 mod ip;
+mod keyfile;
+mod mac_vlan;
+mod ovs;
+mod veth;
+mod vlan;
+mod vrf;
+mod vxlan;
+mod wired;
 
-pub use crate::connection::bridge::NmSettingBridge;
+pub use crate::connection::bond::NmSettingBond;
+pub use crate::connection::bridge::{
+    NmSettingBridge, NmSettingBridgePort, NmSettingBridgeVlanRange,
+};
 pub use crate::connection::conn::{NmConnection, NmSettingConnection};
-pub use crate::connection::ip::{NmSettingIp, NmSettingIpMethod};
+pub use crate::connection::ethtool::NmSettingEthtool;
+pub use crate::connection::ip::{
+    NmIpRoute, NmIpRouteRule, NmSettingIp, NmSettingIpMethod,
+};
+pub use crate::connection::mac_vlan::NmSettingMacVlan;
+pub use crate::connection::ovs::{NmSettingOvsBridge, NmSettingOvsIface};
+pub use crate::connection::veth::NmSettingVeth;
+pub use crate::connection::vlan::NmSettingVlan;
+pub use crate::connection::vrf::NmSettingVrf;
+pub use crate::connection::vxlan::NmSettingVxlan;
+pub use crate::connection::wired::NmSettingWired;
 
 pub(crate) use crate::connection::conn::{
     NmConnectionDbusOwnedValue, NmConnectionDbusValue,
@@ -0,0 +1,96 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::{
+    dbus_value::{value_hash_get_bool, value_hash_get_string},
+    error::NmError,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmSettingOvsBridge {
+    pub stp: Option<bool>,
+    pub rstp: Option<bool>,
+    pub mcast_snooping_enable: Option<bool>,
+    pub fail_mode: Option<String>,
+}
+
+impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingOvsBridge {
+    type Error = NmError;
+    fn try_from(
+        value: &HashMap<String, zvariant::OwnedValue>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            stp: value_hash_get_bool(value, "stp-enable")?,
+            rstp: value_hash_get_bool(value, "rstp-enable")?,
+            mcast_snooping_enable: value_hash_get_bool(
+                value,
+                "mcast-snooping-enable",
+            )?,
+            fail_mode: value_hash_get_string(value, "fail-mode")?,
+        })
+    }
+}
+
+impl NmSettingOvsBridge {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = self.stp {
+            ret.insert("stp-enable", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.rstp {
+            ret.insert("rstp-enable", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.mcast_snooping_enable {
+            ret.insert("mcast-snooping-enable", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.fail_mode {
+            ret.insert("fail-mode", zvariant::Value::new(v.as_str()));
+        }
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmSettingOvsIface {
+    pub iface_type: Option<String>,
+}
+
+impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingOvsIface {
+    type Error = NmError;
+    fn try_from(
+        value: &HashMap<String, zvariant::OwnedValue>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            iface_type: value_hash_get_string(value, "type")?,
+        })
+    }
+}
+
+impl NmSettingOvsIface {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.iface_type {
+            ret.insert("type", zvariant::Value::new(v.as_str()));
+        }
+        Ok(ret)
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::{
+    dbus_value::{
+        value_hash_get_bool, value_hash_get_string, value_hash_get_u32,
+    },
+    error::NmError,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmSettingWired {
+    pub cloned_mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    pub auto_negotiate: Option<bool>,
+    pub speed: Option<u32>,
+    pub duplex: Option<String>,
+}
+
+// The `cloned-mac-address` property is exposed as byte array in DBUS, the
+// `assigned-mac-address` is its string form.
+impl TryFrom<&HashMap<String, zvariant::OwnedValue>> for NmSettingWired {
+    type Error = NmError;
+    fn try_from(
+        value: &HashMap<String, zvariant::OwnedValue>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            cloned_mac_address: value_hash_get_string(
+                value,
+                "assigned-mac-address",
+            )?,
+            mtu: value_hash_get_u32(value, "mtu")?,
+            auto_negotiate: value_hash_get_bool(value, "auto-negotiate")?,
+            speed: value_hash_get_u32(value, "speed")?,
+            duplex: value_hash_get_string(value, "duplex")?,
+        })
+    }
+}
+
+impl NmSettingWired {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.cloned_mac_address {
+            ret.insert("assigned-mac-address", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = self.mtu {
+            ret.insert("mtu", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.auto_negotiate {
+            ret.insert("auto-negotiate", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.speed {
+            ret.insert("speed", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.duplex {
+            ret.insert("duplex", zvariant::Value::new(v.clone()));
+        }
+        Ok(ret)
+    }
+}
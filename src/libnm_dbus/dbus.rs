@@ -169,6 +169,10 @@ impl<'a> NmDbus<'a> {
         Ok(self.proxy.deactivate_connection(&str_to_obj_path(nm_ac)?)?)
     }
 
+    pub(crate) fn hostname_set(&self, hostname: &str) -> Result<(), NmError> {
+        Ok(self.setting_proxy.save_hostname(hostname)?)
+    }
+
     pub(crate) fn connection_add(
         &self,
         nm_conn: &NmConnection,
@@ -107,4 +107,7 @@ trait NetworkManagerSetting {
 
     /// ListConnections method
     fn list_connections(&self) -> zbus::Result<Vec<zvariant::OwnedObjectPath>>;
+
+    /// SaveHostname method
+    fn save_hostname(&self, hostname: &str) -> zbus::Result<()>;
 }
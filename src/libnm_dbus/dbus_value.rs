@@ -13,12 +13,17 @@
 // limitations under the License.
 
 use crate::error::{ErrorKind, NmError};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
 const DBUS_SIGNATURE_STRING: &str = "s";
 const DBUS_SIGNATURE_BOOL: &str = "b";
 const DBUS_SIGNATURE_I32: &str = "i";
+const DBUS_SIGNATURE_U32: &str = "u";
+const DBUS_SIGNATURE_U64: &str = "t";
+const DBUS_SIGNATURE_I64: &str = "x";
 const DBUS_SIGNATURE_ARRAY: &str = "a";
+const DBUS_SIGNATURE_STRING_DICT: &str = "a{ss}";
 
 fn own_value_to_string(
     value: &zvariant::OwnedValue,
@@ -57,6 +62,42 @@ fn own_value_to_i32(value: &zvariant::OwnedValue) -> Result<i32, NmError> {
     }
 }
 
+// TODO: Use macro instead
+fn own_value_to_u32(value: &zvariant::OwnedValue) -> Result<u32, NmError> {
+    check_value_is_u32(value)?;
+    match <u32>::try_from(value) {
+        Ok(s) => Ok(s),
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to convert {:?} to u32: {}", &value, e),
+        )),
+    }
+}
+
+// TODO: Use macro instead
+fn own_value_to_u64(value: &zvariant::OwnedValue) -> Result<u64, NmError> {
+    check_value_is_u64(value)?;
+    match <u64>::try_from(value) {
+        Ok(s) => Ok(s),
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to convert {:?} to u64: {}", &value, e),
+        )),
+    }
+}
+
+// TODO: Use macro instead
+fn own_value_to_i64(value: &zvariant::OwnedValue) -> Result<i64, NmError> {
+    check_value_is_i64(value)?;
+    match <i64>::try_from(value) {
+        Ok(s) => Ok(s),
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to convert {:?} to i64: {}", &value, e),
+        )),
+    }
+}
+
 // TODO: Use macro instead
 fn own_value_to_array(
     value: &zvariant::OwnedValue,
@@ -71,6 +112,26 @@ fn own_value_to_array(
     }
 }
 
+// TODO: Use macro instead
+fn own_value_to_string_hash(
+    value: &zvariant::OwnedValue,
+) -> Result<HashMap<String, String>, NmError> {
+    check_value_is_string_dict(value)?;
+    match <&zvariant::Dict>::try_from(value) {
+        Ok(d) => match HashMap::<String, String>::try_from(d.clone()) {
+            Ok(h) => Ok(h),
+            Err(e) => Err(NmError::new(
+                ErrorKind::Bug,
+                format!("Failed to convert {:?} to hashmap: {}", &value, e),
+            )),
+        },
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to convert {:?} to dict: {}", &value, e),
+        )),
+    }
+}
+
 fn check_value_is_string(value: &zvariant::OwnedValue) -> Result<(), NmError> {
     if value.value_signature().as_str() != DBUS_SIGNATURE_STRING {
         Err(NmError::new(
@@ -104,6 +165,39 @@ fn check_value_is_i32(value: &zvariant::OwnedValue) -> Result<(), NmError> {
     }
 }
 
+fn check_value_is_u32(value: &zvariant::OwnedValue) -> Result<(), NmError> {
+    if value.value_signature().as_str() != DBUS_SIGNATURE_U32 {
+        Err(NmError::new(
+            ErrorKind::Bug,
+            format!("OwnedValue {:?} is not u32", &value),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_value_is_u64(value: &zvariant::OwnedValue) -> Result<(), NmError> {
+    if value.value_signature().as_str() != DBUS_SIGNATURE_U64 {
+        Err(NmError::new(
+            ErrorKind::Bug,
+            format!("OwnedValue {:?} is not u64", &value),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_value_is_i64(value: &zvariant::OwnedValue) -> Result<(), NmError> {
+    if value.value_signature().as_str() != DBUS_SIGNATURE_I64 {
+        Err(NmError::new(
+            ErrorKind::Bug,
+            format!("OwnedValue {:?} is not i64", &value),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 fn check_value_is_array(value: &zvariant::OwnedValue) -> Result<(), NmError> {
     if !value
         .value_signature()
@@ -119,6 +213,19 @@ fn check_value_is_array(value: &zvariant::OwnedValue) -> Result<(), NmError> {
     }
 }
 
+fn check_value_is_string_dict(
+    value: &zvariant::OwnedValue,
+) -> Result<(), NmError> {
+    if value.value_signature().as_str() != DBUS_SIGNATURE_STRING_DICT {
+        Err(NmError::new(
+            ErrorKind::Bug,
+            format!("OwnedValue {:?} is not string dict", &value),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 pub(crate) fn value_hash_get_string(
     value_hashmap: &std::collections::HashMap<String, zvariant::OwnedValue>,
     key: &str,
@@ -152,6 +259,39 @@ pub(crate) fn value_hash_get_i32(
     }
 }
 
+pub(crate) fn value_hash_get_u32(
+    value_hashmap: &std::collections::HashMap<String, zvariant::OwnedValue>,
+    key: &str,
+) -> Result<Option<u32>, NmError> {
+    if let Some(value) = value_hashmap.get(key) {
+        Ok(Some(own_value_to_u32(value)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub(crate) fn value_hash_get_u64(
+    value_hashmap: &std::collections::HashMap<String, zvariant::OwnedValue>,
+    key: &str,
+) -> Result<Option<u64>, NmError> {
+    if let Some(value) = value_hashmap.get(key) {
+        Ok(Some(own_value_to_u64(value)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub(crate) fn value_hash_get_i64(
+    value_hashmap: &std::collections::HashMap<String, zvariant::OwnedValue>,
+    key: &str,
+) -> Result<Option<i64>, NmError> {
+    if let Some(value) = value_hashmap.get(key) {
+        Ok(Some(own_value_to_i64(value)?))
+    } else {
+        Ok(None)
+    }
+}
+
 pub(crate) fn value_hash_get_array<'a>(
     value_hashmap: &'a std::collections::HashMap<String, zvariant::OwnedValue>,
     key: &str,
@@ -163,6 +303,43 @@ pub(crate) fn value_hash_get_array<'a>(
     }
 }
 
+pub(crate) fn value_hash_get_string_array(
+    value_hashmap: &std::collections::HashMap<String, zvariant::OwnedValue>,
+    key: &str,
+) -> Result<Option<Vec<String>>, NmError> {
+    if let Some(value) = value_hashmap.get(key) {
+        let mut ret = Vec::new();
+        for item in own_value_to_array(value)?.iter() {
+            match <&str>::try_from(item) {
+                Ok(s) => ret.push(s.to_string()),
+                Err(e) => {
+                    return Err(NmError::new(
+                        ErrorKind::Bug,
+                        format!(
+                            "Failed to convert {:?} to string: {}",
+                            item, e
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(Some(ret))
+    } else {
+        Ok(None)
+    }
+}
+
+pub(crate) fn value_hash_get_string_hash(
+    value_hashmap: &std::collections::HashMap<String, zvariant::OwnedValue>,
+    key: &str,
+) -> Result<Option<HashMap<String, String>>, NmError> {
+    if let Some(value) = value_hashmap.get(key) {
+        Ok(Some(own_value_to_string_hash(value)?))
+    } else {
+        Ok(None)
+    }
+}
+
 pub(crate) fn value_dict_get_string(
     value_dict: &zvariant::Dict,
     key: &str,
@@ -202,3 +379,83 @@ pub(crate) fn value_dict_get_u32(
         )),
     }
 }
+
+pub(crate) fn value_dict_get_u16(
+    value_dict: &zvariant::Dict,
+    key: &str,
+) -> Result<Option<u16>, NmError> {
+    match value_dict.get::<str, zvariant::Value>(key) {
+        Ok(Some(value)) => match <u16>::try_from(value) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) => Err(NmError::new(
+                ErrorKind::Bug,
+                format!("Failed to convert {:?} to u16: {}", &value, e),
+            )),
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to get {} from {:?}: {}", key, value_dict, e),
+        )),
+    }
+}
+
+pub(crate) fn value_dict_get_u8(
+    value_dict: &zvariant::Dict,
+    key: &str,
+) -> Result<Option<u8>, NmError> {
+    match value_dict.get::<str, zvariant::Value>(key) {
+        Ok(Some(value)) => match <u8>::try_from(value) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) => Err(NmError::new(
+                ErrorKind::Bug,
+                format!("Failed to convert {:?} to u8: {}", &value, e),
+            )),
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to get {} from {:?}: {}", key, value_dict, e),
+        )),
+    }
+}
+
+pub(crate) fn value_dict_get_i32(
+    value_dict: &zvariant::Dict,
+    key: &str,
+) -> Result<Option<i32>, NmError> {
+    match value_dict.get::<str, zvariant::Value>(key) {
+        Ok(Some(value)) => match <i32>::try_from(value) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) => Err(NmError::new(
+                ErrorKind::Bug,
+                format!("Failed to convert {:?} to i32: {}", &value, e),
+            )),
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to get {} from {:?}: {}", key, value_dict, e),
+        )),
+    }
+}
+
+pub(crate) fn value_dict_get_bool(
+    value_dict: &zvariant::Dict,
+    key: &str,
+) -> Result<Option<bool>, NmError> {
+    match value_dict.get::<str, zvariant::Value>(key) {
+        Ok(Some(value)) => match <bool>::try_from(value) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) => Err(NmError::new(
+                ErrorKind::Bug,
+                format!("Failed to convert {:?} to bool: {}", &value, e),
+            )),
+        },
+        Ok(None) => Ok(None),
+        Err(e) => Err(NmError::new(
+            ErrorKind::Bug,
+            format!("Failed to get {} from {:?}: {}", key, value_dict, e),
+        )),
+    }
+}
@@ -22,8 +22,11 @@ mod nm_api;
 
 pub use crate::active_connection::NmActiveConnection;
 pub use crate::connection::{
-    NmConnection, NmSettingBridge, NmSettingConnection, NmSettingIp,
-    NmSettingIpMethod,
+    NmConnection, NmIpRoute, NmIpRouteRule, NmSettingBond, NmSettingBridge,
+    NmSettingBridgePort, NmSettingBridgeVlanRange, NmSettingConnection,
+    NmSettingEthtool, NmSettingIp, NmSettingIpMethod, NmSettingMacVlan,
+    NmSettingOvsBridge, NmSettingOvsIface, NmSettingVeth, NmSettingVlan,
+    NmSettingVrf, NmSettingVxlan, NmSettingWired,
 };
 pub use crate::error::{ErrorKind, NmError};
 pub use crate::nm_api::NmApi;
@@ -163,6 +163,18 @@ impl<'a> NmApi<'a> {
         uuid::Uuid::new_v4().to_hyphenated().to_string()
     }
 
+    // Name based UUID, the same interface always gets the same UUID which is
+    // useful for generating configuration offline. Interface type does not
+    // contain colon, hence the name is not ambiguous.
+    pub fn uuid_gen_by_iface(iface_name: &str, iface_type: &str) -> String {
+        uuid::Uuid::new_v5(
+            &uuid::Uuid::NAMESPACE_OID,
+            format!("{}:{}", iface_type, iface_name).as_bytes(),
+        )
+        .to_hyphenated()
+        .to_string()
+    }
+
     pub fn nm_active_connections_get(
         &self,
     ) -> Result<Vec<NmActiveConnection>, NmError> {
@@ -185,6 +197,11 @@ impl<'a> NmApi<'a> {
         self.dbus
             .checkpoint_timeout_extend(checkpoint, added_time_sec)
     }
+
+    // Save the static hostname, empty string means removing it
+    pub fn hostname_set(&self, hostname: &str) -> Result<(), NmError> {
+        self.dbus.hostname_set(hostname)
+    }
 }
 
 fn get_nm_ac_obj_path_by_uuid(